@@ -1,8 +1,141 @@
 use ppsc_build::Config;
 
+/// Top-level enums declared in `message.proto`.
+const ENUMS: [&str; 9] = [
+    "HashScheme",
+    "SignatureScheme",
+    "MessageType",
+    "FarcasterNetwork",
+    "UserDataType",
+    "CastType",
+    "ReactionType",
+    "Protocol",
+    "UserNameType",
+];
+
+/// `oneof` fields, which hub JSON writes as the key of the set variant, e.g. `castAddBody`.
+///
+/// Paths here and below are relative: a fully qualified path also matches its prefixes, so it
+/// would reach the fields of the `oneof` variants as well.
+const ONEOFS: [&str; 5] = [
+    "MessageData.body",
+    "Embed.embed",
+    "CastAddBody.parent",
+    "ReactionBody.target",
+    "LinkBody.target",
+];
+
+/// `i32` enum fields and the `json` module that writes them by name.
+const ENUM_FIELDS: [(&str, &str); 10] = [
+    ("Message.hash_scheme", "hash_scheme"),
+    ("Message.signature_scheme", "signature_scheme"),
+    ("MessageData.type", "message_type"),
+    ("MessageData.network", "farcaster_network"),
+    ("UserDataBody.type", "user_data_type"),
+    ("CastAddBody.type", "cast_type"),
+    ("ReactionBody.type", "reaction_type"),
+    ("VerificationAddAddressBody.protocol", "protocol"),
+    ("VerificationRemoveBody.protocol", "protocol"),
+    ("UserNameProof.type", "user_name_type"),
+];
+
+/// Bytes fields hub JSON writes as `0x`-prefixed hex. Other bytes fields are base64.
+const HEX_FIELDS: [&str; 10] = [
+    "Message.hash",
+    "Message.signer",
+    "CastId.hash",
+    "CastRemoveBody.target_hash",
+    "VerificationAddAddressBody.address",
+    "VerificationAddAddressBody.block_hash",
+    "VerificationRemoveBody.address",
+    "FrameActionBody.transaction_id",
+    "FrameActionBody.address",
+    "UserNameProof.owner",
+];
+
+const BASE64_FIELDS: [&str; 7] = [
+    "Message.signature",
+    "VerificationAddAddressBody.claim_signature",
+    "FrameActionBody.url",
+    "FrameActionBody.input_text",
+    "FrameActionBody.state",
+    "UserNameProof.name",
+    "UserNameProof.signature",
+];
+
+/// Optional fields, which hub JSON leaves out when unset.
+const OPTIONAL_FIELDS: [&str; 3] = [
+    "Message.data",
+    "LinkBody.display_timestamp",
+    "FrameActionBody.cast_id",
+];
+
+/// Wraps a serde attribute so it only applies with the `serde` feature.
+fn serde(attribute: &str) -> String {
+    format!("#[cfg_attr(feature = \"serde\", {attribute})]")
+}
+
 fn main() {
-    Config::new()
+    let mut config = Config::new();
+    config
         .out_dir("src")
+        // Runtimes store, compare and emit messages, and describe them in metadata.
+        .type_attribute(
+            ".",
+            "#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]",
+        )
+        // Protobuf decoding merges fields into a default-initialised message.
+        .message_attribute(".", "#[derive(Default)]")
+        // Off-chain tools read and write messages in the JSON shape of the hub HTTP API.
+        // Proto enums serialize by name through the `json` module instead.
+        .message_attribute(
+            ".",
+            serde(
+                "derive(serde::Serialize, serde::Deserialize), \
+                 serde(default, rename_all = \"camelCase\")",
+            ),
+        )
+        .field_attribute(
+            "Message.data_bytes",
+            serde(
+                "serde(with = \"crate::json::optional_base64_bytes\", \
+                 skip_serializing_if = \"Option::is_none\")",
+            ),
+        );
+    // Proto enums are plain values that configuration and errors hold by copy.
+    for name in ENUMS {
+        config.enum_attribute(format!(".message.{name}"), "#[derive(Copy)]");
+    }
+    for oneof in ONEOFS {
+        config
+            .enum_attribute(
+                oneof,
+                serde(
+                    "derive(serde::Serialize, serde::Deserialize), \
+                     serde(rename_all = \"camelCase\")",
+                ),
+            )
+            .field_attribute(oneof, serde("serde(flatten)"));
+    }
+    for (field, module) in ENUM_FIELDS {
+        config.field_attribute(
+            field,
+            serde(&format!("serde(with = \"crate::json::{module}\")")),
+        );
+    }
+    for field in HEX_FIELDS {
+        config.field_attribute(field, serde("serde(with = \"crate::json::hex_bytes\")"));
+    }
+    for field in BASE64_FIELDS {
+        config.field_attribute(field, serde("serde(with = \"crate::json::base64_bytes\")"));
+    }
+    for field in OPTIONAL_FIELDS {
+        config.field_attribute(
+            field,
+            serde("serde(skip_serializing_if = \"Option::is_none\")"),
+        );
+    }
+    config
         .compile_protos(
             &["protobufs/schemas/message.proto"],
             &["protobufs/schemas/"],
@@ -0,0 +1,268 @@
+//! `farcaster-frame`: decodes, inspects, verifies and converts Farcaster messages.
+//!
+//! Exits with 0 on success, 1 when the message fails to decode or validate, and 2 on bad
+//! usage or unreadable input.
+use base64::prelude::*;
+use pallet_farcaster_frame::message::{FarcasterNetwork, Message, MessageType};
+use pallet_farcaster_frame::{
+    encode_message, encode_proto_message, parse_message, parse_scale_message, validate_message,
+    Error, FarcasterTime, ValidationConfig,
+};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "\
+Usage: farcaster-frame <COMMAND> [OPTIONS] [MESSAGE]
+
+Commands:
+  decode    Print the message as JSON
+  verify    Validate the message, including its hash and signature
+  convert   Re-encode the message with --to
+
+MESSAGE is hex (optionally 0x-prefixed) or base64, read as hex when it is 0x-prefixed or
+decodes as hex; use --encoding to choose, or --file to read it from a file instead.
+
+Options:
+  --file <PATH>              Read the message from a file, as raw bytes unless --encoding is given
+  --encoding <hex|base64>    Encoding of MESSAGE or of the --file contents
+  --from <protobuf|scale>    Encoding of the message [default: protobuf]
+  --to <protobuf|scale>      Encoding to convert to (convert)
+  --output <hex|base64>      Text encoding of the converted message [default: hex]
+  --network <NAME>           Network the message must be for, e.g. mainnet (verify)
+  --type <NAME>              Accepted message type, e.g. frame_action; repeatable (verify)
+  --max-age <SECONDS>        Reject messages older than this (verify); messages from the
+                             future are always rejected by the system clock
+  -h, --help                 Print this help";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Protobuf,
+    Scale,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Encoding {
+    Hex,
+    Base64,
+}
+
+enum Command {
+    Decode,
+    Verify,
+    Convert,
+}
+
+struct Args {
+    command: Command,
+    message: Option<String>,
+    file: Option<String>,
+    encoding: Option<Encoding>,
+    from: Format,
+    to: Option<Format>,
+    output: Encoding,
+    network: Option<FarcasterNetwork>,
+    message_types: Vec<MessageType>,
+    max_age: Option<u32>,
+}
+
+/// A failure and the exit code it maps to.
+enum Failure {
+    Usage(String),
+    Message(Error),
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Failure::Message(error)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match parse_args(args).and_then(run) {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(Failure::Message(error)) => {
+            eprintln!("error: {error:?}");
+            ExitCode::from(1)
+        }
+        Err(Failure::Usage(message)) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Args, Failure> {
+    let mut args = args.into_iter();
+    let command = match args.next().as_deref() {
+        Some("decode") => Command::Decode,
+        Some("verify") => Command::Verify,
+        Some("convert") => Command::Convert,
+        Some(other) => return Err(usage(format!("unknown command `{other}`"))),
+        None => return Err(usage("missing command")),
+    };
+    let mut parsed = Args {
+        command,
+        message: None,
+        file: None,
+        encoding: None,
+        from: Format::Protobuf,
+        to: None,
+        output: Encoding::Hex,
+        network: None,
+        message_types: Vec::new(),
+        max_age: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| usage(format!("`{arg}` needs a value")))
+        };
+        match arg.as_str() {
+            "--file" => parsed.file = Some(value()?),
+            "--encoding" => parsed.encoding = Some(parse_encoding(&value()?)?),
+            "--from" => parsed.from = parse_format(&value()?)?,
+            "--to" => parsed.to = Some(parse_format(&value()?)?),
+            "--output" => parsed.output = parse_encoding(&value()?)?,
+            "--network" => {
+                let name = value()?;
+                let network =
+                    from_name(&name, "FARCASTER_NETWORK_", FarcasterNetwork::from_str_name)
+                        .ok_or_else(|| usage(format!("unknown network `{name}`")))?;
+                parsed.network = Some(network);
+            }
+            "--type" => {
+                let name = value()?;
+                let message_type = from_name(&name, "MESSAGE_TYPE_", MessageType::from_str_name)
+                    .ok_or_else(|| usage(format!("unknown message type `{name}`")))?;
+                parsed.message_types.push(message_type);
+            }
+            "--max-age" => {
+                let seconds = value()?;
+                let max_age = seconds
+                    .parse()
+                    .map_err(|_| usage(format!("invalid --max-age `{seconds}`")))?;
+                parsed.max_age = Some(max_age);
+            }
+            flag if flag.starts_with("--") => {
+                return Err(usage(format!("unknown option `{flag}`")))
+            }
+            _ if parsed.message.is_none() => parsed.message = Some(arg),
+            _ => return Err(usage(format!("unexpected argument `{arg}`"))),
+        }
+    }
+    Ok(parsed)
+}
+
+fn run(args: Args) -> Result<String, Failure> {
+    let bytes = read_input(&args)?;
+    let msg = match args.from {
+        Format::Protobuf => parse_message(bytes)?,
+        Format::Scale => parse_scale_message(bytes)?,
+    };
+    match args.command {
+        Command::Decode => Ok(to_json(&msg)),
+        Command::Verify => {
+            let config = ValidationConfig {
+                network: args.network,
+                now: Some(system_time()?),
+                max_age: args.max_age,
+                message_types: &args.message_types,
+                ..Default::default()
+            };
+            validate_message(&msg, &config)?;
+            Ok("valid".to_owned())
+        }
+        Command::Convert => {
+            let encoded = match args.to {
+                Some(Format::Protobuf) => encode_proto_message(&msg)?,
+                Some(Format::Scale) => encode_message(&msg)?,
+                None => return Err(usage("convert needs --to")),
+            };
+            Ok(match args.output {
+                Encoding::Hex => hex::encode(encoded),
+                Encoding::Base64 => BASE64_STANDARD.encode(encoded),
+            })
+        }
+    }
+}
+
+fn read_input(args: &Args) -> Result<Vec<u8>, Failure> {
+    let text = match (&args.file, &args.message) {
+        (Some(_), Some(_)) => return Err(usage("pass either MESSAGE or --file")),
+        (Some(path), None) => {
+            let contents = std::fs::read(path)
+                .map_err(|error| usage(format!("cannot read `{path}`: {error}")))?;
+            if args.encoding.is_none() {
+                return Ok(contents);
+            }
+            String::from_utf8(contents).map_err(|_| usage(format!("`{path}` is not text")))?
+        }
+        (None, Some(message)) => message.clone(),
+        (None, None) => return Err(usage("missing MESSAGE")),
+    };
+    let text = text.trim();
+    let prefixed = text.strip_prefix("0x");
+    match args.encoding {
+        Some(Encoding::Hex) => {
+            hex::decode(prefixed.unwrap_or(text)).map_err(|_| usage("MESSAGE is not valid hex"))
+        }
+        Some(Encoding::Base64) => BASE64_STANDARD
+            .decode(text)
+            .map_err(|_| usage("MESSAGE is not valid base64")),
+        // Only `0x`-prefixed input, or input that decodes as hex as a whole, is read as hex.
+        None => match prefixed {
+            Some(hex) => hex::decode(hex).ok(),
+            None => hex::decode(text)
+                .ok()
+                .or_else(|| BASE64_STANDARD.decode(text).ok()),
+        }
+        .ok_or_else(|| {
+            usage("MESSAGE is neither hex nor base64; set its encoding with --encoding")
+        }),
+    }
+}
+
+fn to_json(msg: &Message) -> String {
+    serde_json::to_string_pretty(msg).expect("messages always serialize")
+}
+
+fn system_time() -> Result<FarcasterTime, Failure> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Failure::Message(Error::InvalidTimestamp))?;
+    Ok(FarcasterTime::from_unix_seconds(now.as_secs())?)
+}
+
+/// Looks up a proto enum by its full name or by the name without `prefix`, in any case.
+fn from_name<T>(name: &str, prefix: &str, from_str_name: fn(&str) -> Option<T>) -> Option<T> {
+    let upper = name.to_ascii_uppercase().replace('-', "_");
+    from_str_name(&upper).or_else(|| from_str_name(&format!("{prefix}{upper}")))
+}
+
+fn parse_format(value: &str) -> Result<Format, Failure> {
+    match value {
+        "protobuf" => Ok(Format::Protobuf),
+        "scale" => Ok(Format::Scale),
+        _ => Err(usage(format!("unknown format `{value}`"))),
+    }
+}
+
+fn parse_encoding(value: &str) -> Result<Encoding, Failure> {
+    match value {
+        "hex" => Ok(Encoding::Hex),
+        "base64" => Ok(Encoding::Base64),
+        _ => Err(usage(format!("unknown encoding `{value}`"))),
+    }
+}
+
+fn usage(message: impl Into<String>) -> Failure {
+    Failure::Usage(message.into())
+}
@@ -0,0 +1,347 @@
+//! Borrowed views of protobuf-encoded messages.
+//!
+//! [`MessageRef`] walks the bytes of a `Message` in place and hands out slices of the input
+//! instead of copying every field into owned `Vec`s. A runtime that only needs a few fields,
+//! such as the fid and button index of a frame action, can read them without allocating,
+//! and build the owned [`Message`] only when it needs one.
+//!
+//! Embedded messages cannot be merged without copying, so a view rejects an embedded message
+//! field that appears more than once with [`DecodeError::DuplicateField`].
+use crate::limits::*;
+use crate::message::*;
+use crate::protobuf::{DecodeError, ProtoMessage, Reader};
+use crate::verification::{compute_hash, verify_signature_parts};
+use crate::Error;
+
+/// Sets an embedded message field, rejecting a second occurrence.
+fn set_once<'a>(slot: &mut Option<&'a [u8]>, bytes: &'a [u8]) -> Result<(), DecodeError> {
+    if slot.replace(bytes).is_some() {
+        return Err(DecodeError::DuplicateField);
+    }
+    Ok(())
+}
+
+/// A borrowed view of a protobuf-encoded `Message`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageRef<'a> {
+    bytes: &'a [u8],
+    /// Encoded `MessageData` of field `data`.
+    pub data: Option<&'a [u8]>,
+    /// Hash digest of data
+    pub hash: &'a [u8],
+    /// Hash scheme that produced the hash digest
+    pub hash_scheme: i32,
+    /// Signature of the hash digest
+    pub signature: &'a [u8],
+    /// Signature scheme that produced the signature
+    pub signature_scheme: i32,
+    /// Public key or address of the key pair that produced the signature
+    pub signer: &'a [u8],
+    /// Encoded `MessageData` of field `data_bytes`.
+    pub data_bytes: Option<&'a [u8]>,
+}
+
+impl<'a> MessageRef<'a> {
+    /// Reads a `Message` view from protobuf bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The protobuf encoding of a `Message`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MessageRef)` borrowing from `bytes`.
+    /// * `Err(DecodeError)` if the bytes are malformed or over the hub limits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::borrowed::MessageRef;
+    ///
+    /// // `hash` (field 2) holding two bytes.
+    /// let bytes = [0x12, 0x02, 0xab, 0xcd];
+    /// let msg = MessageRef::decode(&bytes).unwrap();
+    /// assert_eq!(msg.hash, &[0xab, 0xcd]);
+    /// ```
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut msg = MessageRef {
+            bytes,
+            ..Default::default()
+        };
+        let mut reader = Reader::new(bytes);
+        while !reader.is_empty() {
+            let (field, wire_type) = reader.read_key()?;
+            match field {
+                1 => set_once(&mut msg.data, reader.read_bytes(wire_type)?)?,
+                2 => msg.hash = reader.read_bytes(wire_type)?,
+                3 => msg.hash_scheme = reader.read_enum(wire_type)?,
+                4 => msg.signature = reader.read_bytes(wire_type)?,
+                5 => msg.signature_scheme = reader.read_enum(wire_type)?,
+                6 => msg.signer = reader.read_bytes(wire_type)?,
+                7 => {
+                    msg.data_bytes = Some(reader.read_bytes_max(wire_type, MAX_DATA_BYTES_LENGTH)?)
+                }
+                _ => reader.skip_field(wire_type)?,
+            }
+        }
+        Ok(msg)
+    }
+
+    /// Returns the encoded `MessageData` the hash covers: `data_bytes` when present,
+    /// otherwise `data`.
+    pub fn signed_data(&self) -> Option<&'a [u8]> {
+        self.data_bytes.or(self.data)
+    }
+
+    /// Reads a view of the `MessageData` the hash covers.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(MessageDataRef))` borrowing from the message bytes.
+    /// * `Ok(None)` if the message carries neither `data` nor `data_bytes`.
+    /// * `Err(DecodeError)` if the `MessageData` is malformed.
+    pub fn message_data(&self) -> Result<Option<MessageDataRef<'a>>, DecodeError> {
+        self.signed_data().map(MessageDataRef::decode).transpose()
+    }
+
+    /// Decodes the owned `Message` this view was read from.
+    pub fn to_message(&self) -> Result<Message, DecodeError> {
+        Message::decode_proto(self.bytes)
+    }
+
+    /// Checks that `hash` is the digest of the signed data, as [`verify_hash`] does.
+    ///
+    /// `data_bytes` is hashed in place. `data` is hashed as received, and re-encoded
+    /// canonically only if that digest does not match.
+    ///
+    /// [`verify_hash`]: crate::verify_hash
+    pub fn verify_hash(&self) -> Result<(), Error> {
+        if HashScheme::try_from(self.hash_scheme) != Ok(HashScheme::Blake3) {
+            return Err(Error::UnsupportedHashScheme);
+        }
+        let matches = |data: &[u8]| self.hash == &compute_hash(data)[..];
+        let matched = match (self.data_bytes, self.data) {
+            (Some(data_bytes), _) => matches(data_bytes),
+            (None, Some(data)) => {
+                matches(data) || matches(&MessageData::decode_proto(data)?.encode_proto())
+            }
+            (None, None) => return Err(Error::MissingData),
+        };
+        if matched {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch)
+        }
+    }
+
+    /// Checks that `signature` was produced by `signer` over `hash`, as [`verify_signature`]
+    /// does.
+    ///
+    /// [`verify_signature`]: crate::verify_signature
+    pub fn verify_signature(&self) -> Result<(), Error> {
+        verify_signature_parts(
+            self.signature_scheme,
+            self.hash,
+            self.signature,
+            self.signer,
+        )
+    }
+}
+
+impl TryFrom<MessageRef<'_>> for Message {
+    type Error = DecodeError;
+
+    fn try_from(msg: MessageRef<'_>) -> Result<Self, DecodeError> {
+        msg.to_message()
+    }
+}
+
+/// A borrowed view of the body of a `MessageData`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyRef<'a> {
+    /// A frame action, decoded in place.
+    FrameActionBody(FrameActionRef<'a>),
+    /// Any other body, as its field number in `MessageData` and its encoding.
+    Other(u32, &'a [u8]),
+}
+
+/// A borrowed view of a protobuf-encoded `MessageData`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MessageDataRef<'a> {
+    bytes: &'a [u8],
+    /// Type of message contained in the body
+    pub r#type: i32,
+    /// Farcaster ID of the user producing the message
+    pub fid: u64,
+    /// Farcaster epoch timestamp in seconds
+    pub timestamp: u32,
+    /// Farcaster network the message is intended for
+    pub network: i32,
+    /// Body of the message, decoded if it is a `FrameActionBody`
+    pub body: Option<BodyRef<'a>>,
+}
+
+impl<'a> MessageDataRef<'a> {
+    /// Reads a `MessageData` view from protobuf bytes.
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut data = MessageDataRef {
+            bytes,
+            ..Default::default()
+        };
+        let mut body: Option<(u32, &'a [u8])> = None;
+        let mut reader = Reader::new(bytes);
+        while !reader.is_empty() {
+            let (field, wire_type) = reader.read_key()?;
+            match field {
+                1 => data.r#type = reader.read_enum(wire_type)?,
+                2 => data.fid = reader.read_uint64(wire_type)?,
+                3 => data.timestamp = reader.read_uint32(wire_type)?,
+                4 => data.network = reader.read_enum(wire_type)?,
+                5..=7 | 9 | 10 | 12 | 14..=17 => {
+                    let bytes = reader.read_bytes(wire_type)?;
+                    // A later variant replaces the body, a repeated one would be merged.
+                    if matches!(body, Some((set, _)) if set == field) {
+                        return Err(DecodeError::DuplicateField);
+                    }
+                    body = Some((field, bytes));
+                }
+                _ => reader.skip_field(wire_type)?,
+            }
+        }
+        data.body = match body {
+            Some((16, bytes)) => Some(BodyRef::FrameActionBody(FrameActionRef::decode(bytes)?)),
+            Some((field, bytes)) => Some(BodyRef::Other(field, bytes)),
+            None => None,
+        };
+        Ok(data)
+    }
+
+    /// Returns the frame action body, if the body is one.
+    pub fn frame_action(&self) -> Option<&FrameActionRef<'a>> {
+        match &self.body {
+            Some(BodyRef::FrameActionBody(body)) => Some(body),
+            _ => None,
+        }
+    }
+
+    /// Decodes the owned `MessageData` this view was read from.
+    pub fn to_message_data(&self) -> Result<MessageData, DecodeError> {
+        MessageData::decode_proto(self.bytes)
+    }
+}
+
+impl TryFrom<MessageDataRef<'_>> for MessageData {
+    type Error = DecodeError;
+
+    fn try_from(data: MessageDataRef<'_>) -> Result<Self, DecodeError> {
+        data.to_message_data()
+    }
+}
+
+/// A borrowed view of a `CastId`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CastIdRef<'a> {
+    /// Fid of the user who created the cast
+    pub fid: u64,
+    /// Hash of the cast
+    pub hash: &'a [u8],
+}
+
+impl<'a> CastIdRef<'a> {
+    /// Reads a `CastId` view from protobuf bytes.
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut cast_id = CastIdRef::default();
+        let mut reader = Reader::new(bytes);
+        while !reader.is_empty() {
+            let (field, wire_type) = reader.read_key()?;
+            match field {
+                1 => cast_id.fid = reader.read_uint64(wire_type)?,
+                2 => cast_id.hash = reader.read_bytes(wire_type)?,
+                _ => reader.skip_field(wire_type)?,
+            }
+        }
+        Ok(cast_id)
+    }
+
+    /// Copies the view into an owned `CastId`.
+    pub fn to_cast_id(&self) -> CastId {
+        CastId {
+            fid: self.fid,
+            hash: self.hash.to_vec(),
+        }
+    }
+}
+
+impl From<CastIdRef<'_>> for CastId {
+    fn from(cast_id: CastIdRef<'_>) -> Self {
+        cast_id.to_cast_id()
+    }
+}
+
+/// A borrowed view of a `FrameActionBody`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FrameActionRef<'a> {
+    /// URL of the Frame triggering the action
+    pub url: &'a [u8],
+    /// The index of the button pressed (1-4)
+    pub button_index: u32,
+    /// The cast which contained the frame url
+    pub cast_id: Option<CastIdRef<'a>>,
+    /// Text input from the user, if present
+    pub input_text: &'a [u8],
+    /// Serialized frame state value
+    pub state: &'a [u8],
+    /// Chain-specific transaction ID for tx actions
+    pub transaction_id: &'a [u8],
+    /// Chain-specific address for tx actions
+    pub address: &'a [u8],
+}
+
+impl<'a> FrameActionRef<'a> {
+    /// Reads a `FrameActionBody` view from protobuf bytes.
+    pub fn decode(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut body = FrameActionRef::default();
+        let mut cast_id = None;
+        let mut reader = Reader::new(bytes);
+        while !reader.is_empty() {
+            let (field, wire_type) = reader.read_key()?;
+            match field {
+                1 => body.url = reader.read_bytes_max(wire_type, MAX_FRAME_URL_LENGTH)?,
+                2 => body.button_index = reader.read_uint32(wire_type)?,
+                3 => set_once(&mut cast_id, reader.read_bytes(wire_type)?)?,
+                4 => {
+                    body.input_text =
+                        reader.read_bytes_max(wire_type, MAX_FRAME_INPUT_TEXT_LENGTH)?
+                }
+                5 => body.state = reader.read_bytes_max(wire_type, MAX_FRAME_STATE_LENGTH)?,
+                6 => {
+                    body.transaction_id =
+                        reader.read_bytes_max(wire_type, MAX_FRAME_TRANSACTION_ID_LENGTH)?
+                }
+                7 => body.address = reader.read_bytes_max(wire_type, MAX_FRAME_ADDRESS_LENGTH)?,
+                _ => reader.skip_field(wire_type)?,
+            }
+        }
+        body.cast_id = cast_id.map(CastIdRef::decode).transpose()?;
+        Ok(body)
+    }
+
+    /// Copies the view into an owned `FrameActionBody`.
+    pub fn to_frame_action_body(&self) -> FrameActionBody {
+        FrameActionBody {
+            url: self.url.to_vec(),
+            button_index: self.button_index,
+            cast_id: self.cast_id.as_ref().map(CastIdRef::to_cast_id),
+            input_text: self.input_text.to_vec(),
+            state: self.state.to_vec(),
+            transaction_id: self.transaction_id.to_vec(),
+            address: self.address.to_vec(),
+        }
+    }
+}
+
+impl From<FrameActionRef<'_>> for FrameActionBody {
+    fn from(body: FrameActionRef<'_>) -> Self {
+        body.to_frame_action_body()
+    }
+}
@@ -0,0 +1,649 @@
+//! Bounded counterparts of the message types for runtime storage.
+//!
+//! The generated types hold unbounded `Vec`s and therefore have no `MaxEncodedLen`. The types
+//! here bound every variable-length field so they can be kept in storage and events.
+//!
+//! Frame actions, which the pallet acts on, have a bounded [`BoundedFrameActionData`] whose
+//! [`BoundedFrameActionBody`] limits can be set per runtime. The other bodies are bounded by the
+//! hub's [`limits`](crate::limits), or by the protocol's address and signature sizes where the
+//! limits have none. `LinkCompactStateBody` has no counterpart, as nothing bounds its fid list.
+//! Messages of any type, including their hash, signature and signer, are kept whole as a
+//! [`BoundedMessage`].
+//!
+//! Bounded strings keep their UTF-8 bytes. Type-dependent limits, such as the text limit of a
+//! `CastType::Cast`, are left to [`validate_limits`].
+use crate::limits::*;
+extern crate alloc;
+use crate::eip712::{ADDRESS_LENGTH, SIGNATURE_LENGTH};
+use crate::message::*;
+use crate::protobuf::ProtoMessage;
+use crate::verification::HASH_LENGTH;
+use crate::Error;
+use alloc::string::String;
+use bounded_collections::{BoundedVec, ConstU32, Get};
+use derive_where::derive_where;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// A `Message` kept as its protobuf encoding, at most `S` bytes long.
+///
+/// Storing the hub encoding keeps `data_bytes`, and with it the hash and signature, intact.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+#[scale_info(skip_type_params(S))]
+pub struct BoundedMessage<S: Get<u32>>(pub BoundedVec<u8, S>);
+
+impl<S: Get<u32>> BoundedMessage<S> {
+    /// Decodes the stored `Message`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Message)` if the stored bytes decode.
+    /// * `Err(Error)` if they do not.
+    pub fn to_message(&self) -> Result<Message, Error> {
+        Ok(Message::decode_proto(&self.0)?)
+    }
+}
+
+impl<S: Get<u32>> TryFrom<&Message> for BoundedMessage<S> {
+    type Error = Error;
+
+    fn try_from(msg: &Message) -> Result<Self, Error> {
+        BoundedVec::try_from(msg.encode_proto())
+            .map(BoundedMessage)
+            .map_err(|_| Error::TooLong)
+    }
+}
+
+/// A `CastId` whose hash is a 20 byte message hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedCastId {
+    /// Fid of the user who created the cast.
+    pub fid: u64,
+    /// Hash of the cast.
+    pub hash: [u8; HASH_LENGTH],
+}
+
+impl TryFrom<CastId> for BoundedCastId {
+    type Error = Error;
+
+    fn try_from(cast_id: CastId) -> Result<Self, Error> {
+        let hash = cast_id.hash[..]
+            .try_into()
+            .map_err(|_| Error::InvalidHash)?;
+        Ok(BoundedCastId {
+            fid: cast_id.fid,
+            hash,
+        })
+    }
+}
+
+impl From<BoundedCastId> for CastId {
+    fn from(cast_id: BoundedCastId) -> Self {
+        CastId {
+            fid: cast_id.fid,
+            hash: cast_id.hash.to_vec(),
+        }
+    }
+}
+
+/// A `FrameActionBody` with bounded fields.
+///
+/// `MaxTransactionLength` bounds both `transaction_id` and `address`.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+#[scale_info(skip_type_params(MaxUrlLength, MaxInputLength, MaxStateLength, MaxTransactionLength))]
+pub struct BoundedFrameActionBody<
+    MaxUrlLength: Get<u32>,
+    MaxInputLength: Get<u32>,
+    MaxStateLength: Get<u32>,
+    MaxTransactionLength: Get<u32>,
+> {
+    /// URL of the frame triggering the action.
+    pub url: BoundedVec<u8, MaxUrlLength>,
+    /// The index of the button pressed.
+    pub button_index: u32,
+    /// The cast which contained the frame URL.
+    pub cast_id: Option<BoundedCastId>,
+    /// Text input from the user.
+    pub input_text: BoundedVec<u8, MaxInputLength>,
+    /// Serialized frame state.
+    pub state: BoundedVec<u8, MaxStateLength>,
+    /// Chain-specific transaction id for tx actions.
+    pub transaction_id: BoundedVec<u8, MaxTransactionLength>,
+    /// Chain-specific address for tx actions.
+    pub address: BoundedVec<u8, MaxTransactionLength>,
+}
+
+/// A `BoundedFrameActionBody` bounded by the hub's [`limits`](crate::limits).
+///
+/// `address` is bounded by `MAX_FRAME_TRANSACTION_ID_LENGTH`; [`validate_limits`] applies the
+/// tighter `MAX_FRAME_ADDRESS_LENGTH`.
+pub type ProtocolFrameActionBody = BoundedFrameActionBody<
+    ConstU32<MAX_FRAME_URL_LENGTH>,
+    ConstU32<MAX_FRAME_INPUT_TEXT_LENGTH>,
+    ConstU32<MAX_FRAME_STATE_LENGTH>,
+    ConstU32<MAX_FRAME_TRANSACTION_ID_LENGTH>,
+>;
+
+impl<U, I, S, T> TryFrom<FrameActionBody> for BoundedFrameActionBody<U, I, S, T>
+where
+    U: Get<u32>,
+    I: Get<u32>,
+    S: Get<u32>,
+    T: Get<u32>,
+{
+    type Error = Error;
+
+    fn try_from(body: FrameActionBody) -> Result<Self, Error> {
+        Ok(BoundedFrameActionBody {
+            url: bound(body.url)?,
+            button_index: body.button_index,
+            cast_id: body.cast_id.map(BoundedCastId::try_from).transpose()?,
+            input_text: bound(body.input_text)?,
+            state: bound(body.state)?,
+            transaction_id: bound(body.transaction_id)?,
+            address: bound(body.address)?,
+        })
+    }
+}
+
+impl<U, I, S, T> From<BoundedFrameActionBody<U, I, S, T>> for FrameActionBody
+where
+    U: Get<u32>,
+    I: Get<u32>,
+    S: Get<u32>,
+    T: Get<u32>,
+{
+    fn from(body: BoundedFrameActionBody<U, I, S, T>) -> Self {
+        FrameActionBody {
+            url: body.url.into_inner(),
+            button_index: body.button_index,
+            cast_id: body.cast_id.map(CastId::from),
+            input_text: body.input_text.into_inner(),
+            state: body.state.into_inner(),
+            transaction_id: body.transaction_id.into_inner(),
+            address: body.address.into_inner(),
+        }
+    }
+}
+
+/// The `MessageData` of a `FrameAction` message, with a bounded body.
+///
+/// Converting other message types fails with `Error::UnexpectedMessageType`, and a body that is
+/// missing or not a `FrameActionBody` with `Error::MissingBody` or `Error::BodyMismatch`.
+#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+#[derive_where(Clone, Debug, PartialEq, Eq)]
+#[scale_info(skip_type_params(MaxUrlLength, MaxInputLength, MaxStateLength, MaxTransactionLength))]
+pub struct BoundedFrameActionData<
+    MaxUrlLength: Get<u32>,
+    MaxInputLength: Get<u32>,
+    MaxStateLength: Get<u32>,
+    MaxTransactionLength: Get<u32>,
+> {
+    /// Fid of the user who performed the action.
+    pub fid: u64,
+    /// Farcaster epoch timestamp in seconds.
+    pub timestamp: u32,
+    /// The `FarcasterNetwork` the message is for.
+    pub network: i32,
+    /// The frame action.
+    pub body:
+        BoundedFrameActionBody<MaxUrlLength, MaxInputLength, MaxStateLength, MaxTransactionLength>,
+}
+
+/// A `BoundedFrameActionData` bounded by the hub's [`limits`](crate::limits).
+pub type ProtocolFrameActionData = BoundedFrameActionData<
+    ConstU32<MAX_FRAME_URL_LENGTH>,
+    ConstU32<MAX_FRAME_INPUT_TEXT_LENGTH>,
+    ConstU32<MAX_FRAME_STATE_LENGTH>,
+    ConstU32<MAX_FRAME_TRANSACTION_ID_LENGTH>,
+>;
+
+impl<U, I, S, T> TryFrom<MessageData> for BoundedFrameActionData<U, I, S, T>
+where
+    U: Get<u32>,
+    I: Get<u32>,
+    S: Get<u32>,
+    T: Get<u32>,
+{
+    type Error = Error;
+
+    fn try_from(data: MessageData) -> Result<Self, Error> {
+        if data.r#type != MessageType::FrameAction as i32 {
+            return Err(Error::UnexpectedMessageType);
+        }
+        let body = match data.body {
+            Some(message_data::Body::FrameActionBody(body)) => body,
+            Some(_) => return Err(Error::BodyMismatch),
+            None => return Err(Error::MissingBody),
+        };
+        Ok(BoundedFrameActionData {
+            fid: data.fid,
+            timestamp: data.timestamp,
+            network: data.network,
+            body: body.try_into()?,
+        })
+    }
+}
+
+impl<U, I, S, T> From<BoundedFrameActionData<U, I, S, T>> for MessageData
+where
+    U: Get<u32>,
+    I: Get<u32>,
+    S: Get<u32>,
+    T: Get<u32>,
+{
+    fn from(data: BoundedFrameActionData<U, I, S, T>) -> Self {
+        MessageData {
+            r#type: MessageType::FrameAction.into(),
+            fid: data.fid,
+            timestamp: data.timestamp,
+            network: data.network,
+            body: Some(message_data::Body::FrameActionBody(data.body.into())),
+        }
+    }
+}
+
+/// Maximum length in bytes of a verified address: 20 for Ethereum, 32 for Solana.
+pub const MAX_VERIFICATION_ADDRESS_LENGTH: u32 = 32;
+
+/// Maximum length in bytes of a verification claim signature, which a contract wallet may
+/// make longer than an ECDSA signature.
+pub const MAX_CLAIM_SIGNATURE_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of a verification block hash.
+pub const MAX_BLOCK_HASH_LENGTH: u32 = 32;
+
+/// A URL of at most `MAX_URL_LENGTH` bytes.
+pub type BoundedUrl = BoundedVec<u8, ConstU32<MAX_URL_LENGTH>>;
+
+/// The cast or URL a cast replies to, embeds or reacts to.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub enum BoundedTarget {
+    /// A cast.
+    CastId(BoundedCastId),
+    /// A URL.
+    Url(BoundedUrl),
+}
+
+impl TryFrom<cast_add_body::Parent> for BoundedTarget {
+    type Error = Error;
+
+    fn try_from(parent: cast_add_body::Parent) -> Result<Self, Error> {
+        Ok(match parent {
+            cast_add_body::Parent::ParentCastId(cast_id) => {
+                BoundedTarget::CastId(cast_id.try_into()?)
+            }
+            cast_add_body::Parent::ParentUrl(url) => BoundedTarget::Url(bound(url.into_bytes())?),
+        })
+    }
+}
+
+impl From<BoundedTarget> for cast_add_body::Parent {
+    fn from(target: BoundedTarget) -> Self {
+        match target {
+            BoundedTarget::CastId(cast_id) => cast_add_body::Parent::ParentCastId(cast_id.into()),
+            BoundedTarget::Url(url) => cast_add_body::Parent::ParentUrl(text(url)),
+        }
+    }
+}
+
+impl TryFrom<embed::Embed> for BoundedTarget {
+    type Error = Error;
+
+    fn try_from(embed: embed::Embed) -> Result<Self, Error> {
+        Ok(match embed {
+            embed::Embed::CastId(cast_id) => BoundedTarget::CastId(cast_id.try_into()?),
+            embed::Embed::Url(url) => BoundedTarget::Url(bound(url.into_bytes())?),
+        })
+    }
+}
+
+impl From<BoundedTarget> for embed::Embed {
+    fn from(target: BoundedTarget) -> Self {
+        match target {
+            BoundedTarget::CastId(cast_id) => embed::Embed::CastId(cast_id.into()),
+            BoundedTarget::Url(url) => embed::Embed::Url(text(url)),
+        }
+    }
+}
+
+impl TryFrom<reaction_body::Target> for BoundedTarget {
+    type Error = Error;
+
+    fn try_from(target: reaction_body::Target) -> Result<Self, Error> {
+        Ok(match target {
+            reaction_body::Target::TargetCastId(cast_id) => {
+                BoundedTarget::CastId(cast_id.try_into()?)
+            }
+            reaction_body::Target::TargetUrl(url) => BoundedTarget::Url(bound(url.into_bytes())?),
+        })
+    }
+}
+
+impl From<BoundedTarget> for reaction_body::Target {
+    fn from(target: BoundedTarget) -> Self {
+        match target {
+            BoundedTarget::CastId(cast_id) => reaction_body::Target::TargetCastId(cast_id.into()),
+            BoundedTarget::Url(url) => reaction_body::Target::TargetUrl(text(url)),
+        }
+    }
+}
+
+/// A `CastAddBody` bounded by the hub's [`limits`](crate::limits).
+///
+/// `text` is bounded by `MAX_LONG_CAST_TEXT_LENGTH` whatever the cast type, and `embeds` and
+/// `embeds_deprecated` by `MAX_EMBEDS` each.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedCastAddBody {
+    /// URLs embedded in the cast, before `embeds` replaced them.
+    pub embeds_deprecated: BoundedVec<BoundedUrl, ConstU32<MAX_EMBEDS>>,
+    /// Fids mentioned in the cast.
+    pub mentions: BoundedVec<u64, ConstU32<MAX_MENTIONS>>,
+    /// The cast or URL the cast replies to.
+    pub parent: Option<BoundedTarget>,
+    /// Text of the cast, as UTF-8.
+    pub text: BoundedVec<u8, ConstU32<MAX_LONG_CAST_TEXT_LENGTH>>,
+    /// Byte positions of the mentions in the text.
+    pub mentions_positions: BoundedVec<u32, ConstU32<MAX_MENTIONS>>,
+    /// URLs or casts embedded in the cast; `None` for an empty `Embed`.
+    pub embeds: BoundedVec<Option<BoundedTarget>, ConstU32<MAX_EMBEDS>>,
+    /// The `CastType` of the cast.
+    pub r#type: i32,
+}
+
+impl TryFrom<CastAddBody> for BoundedCastAddBody {
+    type Error = Error;
+
+    fn try_from(body: CastAddBody) -> Result<Self, Error> {
+        let embeds_deprecated = body
+            .embeds_deprecated
+            .into_iter()
+            .map(|url| bound(url.into_bytes()))
+            .collect::<Result<sp_std::vec::Vec<_>, _>>()?;
+        let embeds = body
+            .embeds
+            .into_iter()
+            .map(|embed| embed.embed.map(BoundedTarget::try_from).transpose())
+            .collect::<Result<sp_std::vec::Vec<_>, _>>()?;
+        Ok(BoundedCastAddBody {
+            embeds_deprecated: bound(embeds_deprecated)?,
+            mentions: bound(body.mentions)?,
+            parent: body.parent.map(BoundedTarget::try_from).transpose()?,
+            text: bound(body.text.into_bytes())?,
+            mentions_positions: bound(body.mentions_positions)?,
+            embeds: bound(embeds)?,
+            r#type: body.r#type,
+        })
+    }
+}
+
+impl From<BoundedCastAddBody> for CastAddBody {
+    fn from(body: BoundedCastAddBody) -> Self {
+        CastAddBody {
+            embeds_deprecated: body.embeds_deprecated.into_iter().map(text).collect(),
+            mentions: body.mentions.into_inner(),
+            parent: body.parent.map(Into::into),
+            text: text(body.text),
+            mentions_positions: body.mentions_positions.into_inner(),
+            embeds: body
+                .embeds
+                .into_iter()
+                .map(|embed| Embed {
+                    embed: embed.map(Into::into),
+                })
+                .collect(),
+            r#type: body.r#type,
+        }
+    }
+}
+
+/// A `CastRemoveBody` whose target is a 20 byte message hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedCastRemoveBody {
+    /// Hash of the cast to remove.
+    pub target_hash: [u8; HASH_LENGTH],
+}
+
+impl TryFrom<CastRemoveBody> for BoundedCastRemoveBody {
+    type Error = Error;
+
+    fn try_from(body: CastRemoveBody) -> Result<Self, Error> {
+        let target_hash = body.target_hash[..]
+            .try_into()
+            .map_err(|_| Error::InvalidHash)?;
+        Ok(BoundedCastRemoveBody { target_hash })
+    }
+}
+
+impl From<BoundedCastRemoveBody> for CastRemoveBody {
+    fn from(body: BoundedCastRemoveBody) -> Self {
+        CastRemoveBody {
+            target_hash: body.target_hash.to_vec(),
+        }
+    }
+}
+
+/// A `ReactionBody` bounded by the hub's [`limits`](crate::limits).
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedReactionBody {
+    /// The `ReactionType`.
+    pub r#type: i32,
+    /// The cast or URL reacted to.
+    pub target: Option<BoundedTarget>,
+}
+
+impl TryFrom<ReactionBody> for BoundedReactionBody {
+    type Error = Error;
+
+    fn try_from(body: ReactionBody) -> Result<Self, Error> {
+        Ok(BoundedReactionBody {
+            r#type: body.r#type,
+            target: body.target.map(BoundedTarget::try_from).transpose()?,
+        })
+    }
+}
+
+impl From<BoundedReactionBody> for ReactionBody {
+    fn from(body: BoundedReactionBody) -> Self {
+        ReactionBody {
+            r#type: body.r#type,
+            target: body.target.map(Into::into),
+        }
+    }
+}
+
+/// A `VerificationAddAddressBody` bounded by the address, signature and block hash sizes of
+/// the verification protocols.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedVerificationAddAddressBody {
+    /// The verified address.
+    pub address: BoundedVec<u8, ConstU32<MAX_VERIFICATION_ADDRESS_LENGTH>>,
+    /// Signature by the address over the verification claim.
+    pub claim_signature: BoundedVec<u8, ConstU32<MAX_CLAIM_SIGNATURE_LENGTH>>,
+    /// Hash of the block the claim was made at.
+    pub block_hash: BoundedVec<u8, ConstU32<MAX_BLOCK_HASH_LENGTH>>,
+    /// 0 for an EOA signature, 1 for a contract signature.
+    pub verification_type: u32,
+    /// Chain id of a contract signature.
+    pub chain_id: u32,
+    /// The `Protocol` of the address.
+    pub protocol: i32,
+}
+
+impl TryFrom<VerificationAddAddressBody> for BoundedVerificationAddAddressBody {
+    type Error = Error;
+
+    fn try_from(body: VerificationAddAddressBody) -> Result<Self, Error> {
+        Ok(BoundedVerificationAddAddressBody {
+            address: bound(body.address)?,
+            claim_signature: bound(body.claim_signature)?,
+            block_hash: bound(body.block_hash)?,
+            verification_type: body.verification_type,
+            chain_id: body.chain_id,
+            protocol: body.protocol,
+        })
+    }
+}
+
+impl From<BoundedVerificationAddAddressBody> for VerificationAddAddressBody {
+    fn from(body: BoundedVerificationAddAddressBody) -> Self {
+        VerificationAddAddressBody {
+            address: body.address.into_inner(),
+            claim_signature: body.claim_signature.into_inner(),
+            block_hash: body.block_hash.into_inner(),
+            verification_type: body.verification_type,
+            chain_id: body.chain_id,
+            protocol: body.protocol,
+        }
+    }
+}
+
+/// A `VerificationRemoveBody` bounded by the address sizes of the verification protocols.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedVerificationRemoveBody {
+    /// The address to remove.
+    pub address: BoundedVec<u8, ConstU32<MAX_VERIFICATION_ADDRESS_LENGTH>>,
+    /// The `Protocol` of the address.
+    pub protocol: i32,
+}
+
+impl TryFrom<VerificationRemoveBody> for BoundedVerificationRemoveBody {
+    type Error = Error;
+
+    fn try_from(body: VerificationRemoveBody) -> Result<Self, Error> {
+        Ok(BoundedVerificationRemoveBody {
+            address: bound(body.address)?,
+            protocol: body.protocol,
+        })
+    }
+}
+
+impl From<BoundedVerificationRemoveBody> for VerificationRemoveBody {
+    fn from(body: BoundedVerificationRemoveBody) -> Self {
+        VerificationRemoveBody {
+            address: body.address.into_inner(),
+            protocol: body.protocol,
+        }
+    }
+}
+
+/// A `UserDataBody` bounded by the hub's [`limits`](crate::limits).
+///
+/// `value` is bounded by `MAX_USER_DATA_LENGTH` whatever the user data type.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedUserDataBody {
+    /// The `UserDataType`.
+    pub r#type: i32,
+    /// The value, as UTF-8.
+    pub value: BoundedVec<u8, ConstU32<MAX_USER_DATA_LENGTH>>,
+}
+
+impl TryFrom<UserDataBody> for BoundedUserDataBody {
+    type Error = Error;
+
+    fn try_from(body: UserDataBody) -> Result<Self, Error> {
+        Ok(BoundedUserDataBody {
+            r#type: body.r#type,
+            value: bound(body.value.into_bytes())?,
+        })
+    }
+}
+
+impl From<BoundedUserDataBody> for UserDataBody {
+    fn from(body: BoundedUserDataBody) -> Self {
+        UserDataBody {
+            r#type: body.r#type,
+            value: text(body.value),
+        }
+    }
+}
+
+/// A `LinkBody` bounded by the hub's [`limits`](crate::limits).
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedLinkBody {
+    /// The link type, as UTF-8.
+    pub r#type: BoundedVec<u8, ConstU32<MAX_LINK_TYPE_LENGTH>>,
+    /// User-defined timestamp that preserves the original link time.
+    pub display_timestamp: Option<u32>,
+    /// Fid of the linked user.
+    pub target_fid: Option<u64>,
+}
+
+impl TryFrom<LinkBody> for BoundedLinkBody {
+    type Error = Error;
+
+    fn try_from(body: LinkBody) -> Result<Self, Error> {
+        Ok(BoundedLinkBody {
+            r#type: bound(body.r#type.into_bytes())?,
+            display_timestamp: body.display_timestamp,
+            target_fid: body.target.map(|link_body::Target::TargetFid(fid)| fid),
+        })
+    }
+}
+
+impl From<BoundedLinkBody> for LinkBody {
+    fn from(body: BoundedLinkBody) -> Self {
+        LinkBody {
+            r#type: text(body.r#type),
+            display_timestamp: body.display_timestamp,
+            target: body.target_fid.map(link_body::Target::TargetFid),
+        }
+    }
+}
+
+/// A `UserNameProof` bounded by `MAX_USERNAME_LENGTH` and the size of an Ethereum address and
+/// signature.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct BoundedUserNameProof {
+    /// Unix timestamp of the proof, in seconds.
+    pub timestamp: u64,
+    /// The username.
+    pub name: BoundedVec<u8, ConstU32<MAX_USERNAME_LENGTH>>,
+    /// Address that owns the username.
+    pub owner: BoundedVec<u8, ConstU32<{ ADDRESS_LENGTH as u32 }>>,
+    /// Signature of the name registry, empty for onchain names.
+    pub signature: BoundedVec<u8, ConstU32<{ SIGNATURE_LENGTH as u32 }>>,
+    /// Fid the username belongs to.
+    pub fid: u64,
+    /// The `UserNameType`.
+    pub r#type: i32,
+}
+
+impl TryFrom<UserNameProof> for BoundedUserNameProof {
+    type Error = Error;
+
+    fn try_from(proof: UserNameProof) -> Result<Self, Error> {
+        Ok(BoundedUserNameProof {
+            timestamp: proof.timestamp,
+            name: bound(proof.name)?,
+            owner: bound(proof.owner)?,
+            signature: bound(proof.signature)?,
+            fid: proof.fid,
+            r#type: proof.r#type,
+        })
+    }
+}
+
+impl From<BoundedUserNameProof> for UserNameProof {
+    fn from(proof: BoundedUserNameProof) -> Self {
+        UserNameProof {
+            timestamp: proof.timestamp,
+            name: proof.name.into_inner(),
+            owner: proof.owner.into_inner(),
+            signature: proof.signature.into_inner(),
+            fid: proof.fid,
+            r#type: proof.r#type,
+        }
+    }
+}
+
+fn bound<T, S: Get<u32>>(items: sp_std::vec::Vec<T>) -> Result<BoundedVec<T, S>, Error> {
+    BoundedVec::try_from(items).map_err(|_| Error::TooLong)
+}
+
+/// The fields are public, so a bounded string may not be UTF-8; invalid bytes are replaced.
+fn text<S: Get<u32>>(bytes: BoundedVec<u8, S>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,303 @@
+//! Building and signing messages without a hub.
+//!
+//! [`MessageBuilder`] assembles the `MessageData` of one message type from typed fields and
+//! signs it with an ed25519 key, filling in the blake3 `hash`, the `signature` over it and the
+//! `signer`. Tests, bots and simulations get messages that pass
+//! [`verify_message`](crate::verify_message) exactly like hub-produced ones.
+extern crate alloc;
+use crate::eip712::ADDRESS_LENGTH;
+use crate::message::*;
+use crate::protobuf::ProtoMessage;
+use crate::time::FarcasterTime;
+use crate::validation::validate_body;
+use crate::verification::{compute_hash, HASH_LENGTH};
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+use ed25519_dalek::Signer;
+
+pub use ed25519_dalek::SigningKey;
+
+/// Builds the data of a single message and signs it.
+#[derive(Clone, Debug)]
+pub struct MessageBuilder {
+    data: MessageData,
+}
+
+impl MessageBuilder {
+    /// Starts a message from `fid`, sent to `network` at `timestamp`.
+    ///
+    /// A body must be set with one of the message type methods before signing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::builder::{MessageBuilder, SigningKey};
+    /// use pallet_farcaster_frame::message::{FarcasterNetwork, UserDataType};
+    /// use pallet_farcaster_frame::{verify_message, FarcasterTime};
+    ///
+    /// let key = SigningKey::from_bytes(&[7; 32]);
+    /// let msg = MessageBuilder::new(2, FarcasterNetwork::Mainnet, FarcasterTime(99_825_232))
+    ///     .user_data_add(UserDataType::Display, "Alice")
+    ///     .sign(&key)
+    ///     .unwrap();
+    /// assert_eq!(verify_message(&msg), Ok(()));
+    /// ```
+    pub fn new(fid: u64, network: FarcasterNetwork, timestamp: FarcasterTime) -> Self {
+        MessageBuilder {
+            data: MessageData {
+                fid,
+                timestamp: timestamp.0,
+                network: network.into(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn body(mut self, message_type: MessageType, body: message_data::Body) -> Self {
+        self.data.r#type = message_type.into();
+        self.data.body = Some(body);
+        self
+    }
+
+    /// Makes the message a `CastAdd` of `text`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cast_type` - Whether the cast is a `Cast` or a `LongCast`.
+    /// * `text` - The text of the cast, without the mentioned usernames.
+    /// * `mentions` - The mentioned fids, each with its byte position in `text`.
+    /// * `embeds` - The URLs or casts embedded in the cast.
+    /// * `parent` - The cast or URL the cast replies to, if any.
+    pub fn cast_add(
+        self,
+        cast_type: CastType,
+        text: &str,
+        mentions: &[(u64, u32)],
+        embeds: Vec<embed::Embed>,
+        parent: Option<cast_add_body::Parent>,
+    ) -> Self {
+        let body = CastAddBody {
+            embeds_deprecated: Vec::new(),
+            mentions: mentions.iter().map(|&(fid, _)| fid).collect(),
+            parent,
+            text: String::from(text),
+            mentions_positions: mentions.iter().map(|&(_, position)| position).collect(),
+            embeds: embeds
+                .into_iter()
+                .map(|embed| Embed { embed: Some(embed) })
+                .collect(),
+            r#type: cast_type.into(),
+        };
+        self.body(MessageType::CastAdd, message_data::Body::CastAddBody(body))
+    }
+
+    /// Makes the message a `CastRemove` of the cast with hash `target_hash`.
+    pub fn cast_remove(self, target_hash: [u8; HASH_LENGTH]) -> Self {
+        let body = CastRemoveBody {
+            target_hash: target_hash.to_vec(),
+        };
+        self.body(
+            MessageType::CastRemove,
+            message_data::Body::CastRemoveBody(body),
+        )
+    }
+
+    /// Makes the message a `ReactionAdd` of `reaction_type` to `target`.
+    pub fn reaction_add(self, reaction_type: ReactionType, target: reaction_body::Target) -> Self {
+        self.reaction(MessageType::ReactionAdd, reaction_type, target)
+    }
+
+    /// Makes the message a `ReactionRemove` of `reaction_type` from `target`.
+    pub fn reaction_remove(
+        self,
+        reaction_type: ReactionType,
+        target: reaction_body::Target,
+    ) -> Self {
+        self.reaction(MessageType::ReactionRemove, reaction_type, target)
+    }
+
+    fn reaction(
+        self,
+        message_type: MessageType,
+        reaction_type: ReactionType,
+        target: reaction_body::Target,
+    ) -> Self {
+        let body = ReactionBody {
+            r#type: reaction_type.into(),
+            target: Some(target),
+        };
+        self.body(message_type, message_data::Body::ReactionBody(body))
+    }
+
+    /// Makes the message a `LinkAdd` of type `link_type`, such as `follow`, to `target_fid`.
+    pub fn link_add(self, link_type: &str, target_fid: u64) -> Self {
+        self.link(MessageType::LinkAdd, link_type, target_fid)
+    }
+
+    /// Makes the message a `LinkRemove` of type `link_type` to `target_fid`.
+    pub fn link_remove(self, link_type: &str, target_fid: u64) -> Self {
+        self.link(MessageType::LinkRemove, link_type, target_fid)
+    }
+
+    fn link(self, message_type: MessageType, link_type: &str, target_fid: u64) -> Self {
+        let body = LinkBody {
+            r#type: String::from(link_type),
+            display_timestamp: None,
+            target: Some(link_body::Target::TargetFid(target_fid)),
+        };
+        self.body(message_type, message_data::Body::LinkBody(body))
+    }
+
+    /// Makes the message a `LinkCompactState` of every `link_type` link to `target_fids`.
+    pub fn link_compact_state(self, link_type: &str, target_fids: Vec<u64>) -> Self {
+        let body = LinkCompactStateBody {
+            r#type: String::from(link_type),
+            target_fids,
+        };
+        self.body(
+            MessageType::LinkCompactState,
+            message_data::Body::LinkCompactStateBody(body),
+        )
+    }
+
+    /// Makes the message a `VerificationAddEthAddress` of `address` on `protocol`.
+    ///
+    /// # Arguments
+    ///
+    /// * `protocol` - The chain family of `address`.
+    /// * `address` - The verified address.
+    /// * `claim_signature` - The signature by `address` over the verification claim.
+    /// * `block_hash` - The hash of the block the claim was made at.
+    /// * `chain_id` - The chain of a contract wallet whose signature is checked through
+    ///   ERC-1271, or `None` for a signature by an externally owned account.
+    pub fn verification_add_address(
+        self,
+        protocol: Protocol,
+        address: Vec<u8>,
+        claim_signature: Vec<u8>,
+        block_hash: Vec<u8>,
+        chain_id: Option<u32>,
+    ) -> Self {
+        let body = VerificationAddAddressBody {
+            address,
+            claim_signature,
+            block_hash,
+            verification_type: u32::from(chain_id.is_some()),
+            chain_id: chain_id.unwrap_or_default(),
+            protocol: protocol.into(),
+        };
+        self.body(
+            MessageType::VerificationAddEthAddress,
+            message_data::Body::VerificationAddAddressBody(body),
+        )
+    }
+
+    /// Makes the message a `VerificationRemove` of `address` on `protocol`.
+    pub fn verification_remove(self, address: Vec<u8>, protocol: Protocol) -> Self {
+        let body = VerificationRemoveBody {
+            address,
+            protocol: protocol.into(),
+        };
+        self.body(
+            MessageType::VerificationRemove,
+            message_data::Body::VerificationRemoveBody(body),
+        )
+    }
+
+    /// Makes the message a `UserDataAdd` setting `data_type` to `value`.
+    pub fn user_data_add(self, data_type: UserDataType, value: &str) -> Self {
+        let body = UserDataBody {
+            r#type: data_type.into(),
+            value: String::from(value),
+        };
+        self.body(
+            MessageType::UserDataAdd,
+            message_data::Body::UserDataBody(body),
+        )
+    }
+
+    /// Makes the message a `UsernameProof` that `name` belongs to the message's fid.
+    ///
+    /// # Arguments
+    ///
+    /// * `name_type` - Whether `name` is an fname or an ENS name.
+    /// * `name` - The username.
+    /// * `owner` - The address that owns the username.
+    /// * `signature` - The name registry's signature over the proof, empty for ENS names.
+    /// * `timestamp` - Unix time of the proof, in seconds.
+    pub fn username_proof(
+        self,
+        name_type: UserNameType,
+        name: &str,
+        owner: [u8; ADDRESS_LENGTH],
+        signature: Vec<u8>,
+        timestamp: u64,
+    ) -> Self {
+        let proof = UserNameProof {
+            timestamp,
+            name: name.as_bytes().to_vec(),
+            owner: owner.to_vec(),
+            signature,
+            fid: self.data.fid,
+            r#type: name_type.into(),
+        };
+        self.body(
+            MessageType::UsernameProof,
+            message_data::Body::UsernameProofBody(proof),
+        )
+    }
+
+    /// Makes the message a `FrameAction` of `body`.
+    pub fn frame_action(self, body: FrameActionBody) -> Self {
+        self.body(
+            MessageType::FrameAction,
+            message_data::Body::FrameActionBody(body),
+        )
+    }
+
+    /// Returns the message data built so far.
+    pub fn data(&self) -> &MessageData {
+        &self.data
+    }
+
+    /// Signs the message with `key`.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The ed25519 app key of the signer, registered for the fid on-chain.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Message)` with `data`, `hash`, `signature` and `signer` filled in.
+    /// * `Err(Error::MissingBody)` if no message type method was called.
+    pub fn sign(self, key: &SigningKey) -> Result<Message, Error> {
+        validate_body(&self.data)?;
+        Ok(sign_message_data(self.data, key))
+    }
+}
+
+/// Hashes and signs `data` as is, with the Blake3 and Ed25519 schemes.
+///
+/// Unlike [`MessageBuilder::sign`], the data is not checked, so this can also produce
+/// well-signed messages that fail validation.
+///
+/// # Arguments
+///
+/// * `data` - The `MessageData` to sign.
+/// * `key` - The ed25519 key to sign with.
+///
+/// # Returns
+///
+/// * The `Message` carrying `data`, its hash, the signature over the hash and the signer.
+pub fn sign_message_data(data: MessageData, key: &SigningKey) -> Message {
+    let hash = compute_hash(&data.encode_proto());
+    Message {
+        data: Some(data),
+        hash: hash.to_vec(),
+        hash_scheme: HashScheme::Blake3.into(),
+        signature: key.sign(&hash).to_bytes().to_vec(),
+        signature_scheme: SignatureScheme::Ed25519.into(),
+        signer: key.verifying_key().to_bytes().to_vec(),
+        data_bytes: None,
+    }
+}
@@ -0,0 +1,285 @@
+//! EIP-712 typed data signatures made by Ethereum addresses.
+//!
+//! Farcaster uses EIP-712 for messages signed with `SignatureScheme::Eip712`, for the claims
+//! in `VerificationAddAddressBody` and for fname `UserNameProof`s. Each signature is an
+//! ECDSA secp256k1 signature from which the signing address is recovered.
+use crate::message::*;
+use crate::Error;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+/// Length in bytes of an Ethereum address.
+pub const ADDRESS_LENGTH: usize = 20;
+
+/// Length in bytes of an ECDSA signature in `r || s || v` form.
+pub const SIGNATURE_LENGTH: usize = 65;
+
+const DOMAIN_TYPE: &[u8] = b"EIP712Domain(string name,string version,bytes32 salt)";
+const DOMAIN_NAME: &[u8] = b"Farcaster Verify Ethereum Address";
+const DOMAIN_VERSION: &[u8] = b"2.0.0";
+const DOMAIN_SALT: [u8; 32] = [
+    0xf2, 0xd8, 0x57, 0xf4, 0xa3, 0xed, 0xcb, 0x9b, 0x78, 0xb4, 0xd5, 0x03, 0xbf, 0xe7, 0x33, 0xdb,
+    0x1e, 0x3f, 0x6c, 0xdc, 0x2b, 0x79, 0x71, 0xee, 0x73, 0x96, 0x26, 0xc9, 0x7e, 0x86, 0xa5, 0x58,
+];
+
+const USERNAME_DOMAIN_TYPE: &[u8] =
+    b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const USERNAME_DOMAIN_NAME: &[u8] = b"Farcaster name verification";
+const USERNAME_DOMAIN_VERSION: &[u8] = b"1";
+const USERNAME_DOMAIN_CHAIN_ID: u64 = 1;
+const USERNAME_DOMAIN_VERIFYING_CONTRACT: [u8; ADDRESS_LENGTH] = [
+    0xe3, 0xbe, 0x01, 0xd9, 0x9b, 0xaa, 0x8d, 0xb9, 0x90, 0x5b, 0x33, 0xa3, 0xca, 0x39, 0x12, 0x38,
+    0x23, 0x4b, 0x79, 0xd1,
+];
+
+const MESSAGE_DATA_TYPE: &[u8] = b"MessageData(bytes hash)";
+const VERIFICATION_CLAIM_TYPE: &[u8] =
+    b"VerificationClaim(uint256 fid,address address,bytes32 blockHash,uint8 network)";
+const USERNAME_PROOF_TYPE: &[u8] = b"UserNameProof(string name,uint256 timestamp,address owner)";
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// ABI-encodes an unsigned integer as a 32 byte word.
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// ABI-encodes an address as a 32 byte word.
+fn address_word(address: &[u8; ADDRESS_LENGTH]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(address);
+    word
+}
+
+/// Hashes the concatenation of 32 byte words, as EIP-712 `hashStruct` does.
+fn hash_words(words: &[[u8; 32]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for word in words {
+        hasher.update(word);
+    }
+    hasher.finalize().into()
+}
+
+fn farcaster_domain_separator() -> [u8; 32] {
+    hash_words(&[
+        keccak256(DOMAIN_TYPE),
+        keccak256(DOMAIN_NAME),
+        keccak256(DOMAIN_VERSION),
+        DOMAIN_SALT,
+    ])
+}
+
+fn username_domain_separator() -> [u8; 32] {
+    hash_words(&[
+        keccak256(USERNAME_DOMAIN_TYPE),
+        keccak256(USERNAME_DOMAIN_NAME),
+        keccak256(USERNAME_DOMAIN_VERSION),
+        uint_word(USERNAME_DOMAIN_CHAIN_ID),
+        address_word(&USERNAME_DOMAIN_VERIFYING_CONTRACT),
+    ])
+}
+
+fn typed_data_digest(domain_separator: [u8; 32], struct_hash: [u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+/// Computes the EIP-712 digest an Ethereum signer signs for a `Message.hash`.
+///
+/// # Arguments
+///
+/// * `hash` - The `Message.hash` being signed.
+///
+/// # Returns
+///
+/// * The 32 byte digest of `MessageData(bytes hash)` under the Farcaster domain.
+pub fn message_hash_digest(hash: &[u8]) -> [u8; 32] {
+    let struct_hash = hash_words(&[keccak256(MESSAGE_DATA_TYPE), keccak256(hash)]);
+    typed_data_digest(farcaster_domain_separator(), struct_hash)
+}
+
+/// Computes the EIP-712 digest an Ethereum address signs to claim it belongs to a fid.
+///
+/// # Arguments
+///
+/// * `fid` - The fid claiming the address.
+/// * `address` - The Ethereum address being verified.
+/// * `block_hash` - The hash of the latest Ethereum block when the claim was signed.
+/// * `network` - The `FarcasterNetwork` the claim is made on.
+///
+/// # Returns
+///
+/// * The 32 byte digest of `VerificationClaim` under the Farcaster domain.
+pub fn verification_claim_digest(
+    fid: u64,
+    address: &[u8; ADDRESS_LENGTH],
+    block_hash: &[u8; 32],
+    network: u8,
+) -> [u8; 32] {
+    let struct_hash = hash_words(&[
+        keccak256(VERIFICATION_CLAIM_TYPE),
+        uint_word(fid),
+        address_word(address),
+        *block_hash,
+        uint_word(u64::from(network)),
+    ]);
+    typed_data_digest(farcaster_domain_separator(), struct_hash)
+}
+
+/// Computes the EIP-712 digest the fname server signs for a `UserNameProof`.
+///
+/// # Arguments
+///
+/// * `name` - The username being proven.
+/// * `timestamp` - The Unix timestamp of the proof.
+/// * `owner` - The custody address that owns the name.
+///
+/// # Returns
+///
+/// * The 32 byte digest of `UserNameProof` under the fname verification domain.
+pub fn username_proof_digest(
+    name: &[u8],
+    timestamp: u64,
+    owner: &[u8; ADDRESS_LENGTH],
+) -> [u8; 32] {
+    let struct_hash = hash_words(&[
+        keccak256(USERNAME_PROOF_TYPE),
+        keccak256(name),
+        uint_word(timestamp),
+        address_word(owner),
+    ]);
+    typed_data_digest(username_domain_separator(), struct_hash)
+}
+
+/// Returns the Ethereum address of a secp256k1 public key.
+pub fn public_key_address(key: &VerifyingKey) -> [u8; ADDRESS_LENGTH] {
+    let point = key.to_encoded_point(false);
+    let hash = keccak256(&point.as_bytes()[1..]);
+    let mut address = [0u8; ADDRESS_LENGTH];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+/// Recovers the Ethereum address that produced `signature` over `digest`.
+///
+/// # Arguments
+///
+/// * `digest` - The 32 byte EIP-712 digest that was signed.
+/// * `signature` - A 65 byte `r || s || v` signature. `v` may be `0`/`1` or `27`/`28`.
+///
+/// # Returns
+///
+/// * `Ok([u8; 20])` with the recovered address.
+/// * `Err(Error::InvalidSignature)` if the signature is malformed or recovery fails.
+pub fn recover_address(digest: &[u8; 32], signature: &[u8]) -> Result<[u8; ADDRESS_LENGTH], Error> {
+    if signature.len() != SIGNATURE_LENGTH {
+        return Err(Error::InvalidSignature);
+    }
+    let v = match signature[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return Err(Error::InvalidSignature),
+    };
+    let mut signature =
+        Signature::from_slice(&signature[..64]).map_err(|_| Error::InvalidSignature)?;
+    let mut recovery_id = RecoveryId::from_byte(v).ok_or(Error::InvalidSignature)?;
+    // `ecrecover` accepts high-s signatures; normalise them the way k256 expects.
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+    let key = VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+        .map_err(|_| Error::InvalidSignature)?;
+    Ok(public_key_address(&key))
+}
+
+fn to_address(bytes: &[u8]) -> Option<&[u8; ADDRESS_LENGTH]> {
+    bytes.try_into().ok()
+}
+
+/// Checks an `Eip712` signed `Message`: `signer` must be the address that signed `hash`.
+pub(crate) fn verify_message_signature(
+    hash: &[u8],
+    signature: &[u8],
+    signer: &[u8],
+) -> Result<(), Error> {
+    let signer = to_address(signer).ok_or(Error::InvalidSigner)?;
+    let recovered = recover_address(&message_hash_digest(hash), signature)?;
+    if &recovered == signer {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// Checks the claim signature of an Ethereum address verification.
+///
+/// Only externally owned accounts can be checked here; contract verifications rely on
+/// ERC-1271 calls to the verified contract.
+///
+/// # Arguments
+///
+/// * `data` - The `MessageData` carrying a `VerificationAddAddressBody`.
+///
+/// # Returns
+///
+/// * `Ok(())` if `claim_signature` was produced by `address`.
+/// * `Err(Error::InvalidMessage)` if the body is not a `VerificationAddAddressBody` or its
+///   address or block hash are malformed.
+/// * `Err(Error::UnsupportedVerification)` for Solana or contract verifications.
+/// * `Err(Error::InvalidSignature)` if the claim signature does not recover to `address`.
+pub fn verify_verification_claim(data: &MessageData) -> Result<(), Error> {
+    let Some(message_data::Body::VerificationAddAddressBody(body)) = &data.body else {
+        return Err(Error::InvalidMessage);
+    };
+    if body.protocol() != Ok(Protocol::Ethereum) || body.verification_type != 0 {
+        return Err(Error::UnsupportedVerification);
+    }
+    let address = to_address(&body.address).ok_or(Error::InvalidMessage)?;
+    let block_hash: &[u8; 32] = body.block_hash[..]
+        .try_into()
+        .map_err(|_| Error::InvalidMessage)?;
+    let network = u8::try_from(data.network).map_err(|_| Error::InvalidMessage)?;
+    let digest = verification_claim_digest(data.fid, address, block_hash, network);
+    if &recover_address(&digest, &body.claim_signature)? == address {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// Checks that an fname `UserNameProof` was signed by the expected fname server address.
+///
+/// # Arguments
+///
+/// * `proof` - The proof to check.
+/// * `server` - The address of the fname server the caller trusts.
+///
+/// # Returns
+///
+/// * `Ok(())` if `proof.signature` was produced by `server`.
+/// * `Err(Error::UnsupportedVerification)` if the proof is not an fname proof.
+/// * `Err(Error::InvalidMessage)` if `proof.owner` is not an address.
+/// * `Err(Error::InvalidSignature)` if the signature does not recover to `server`.
+pub fn verify_username_proof(
+    proof: &UserNameProof,
+    server: &[u8; ADDRESS_LENGTH],
+) -> Result<(), Error> {
+    if proof.username_type() != Ok(UserNameType::UsernameTypeFname) {
+        return Err(Error::UnsupportedVerification);
+    }
+    let owner = to_address(&proof.owner).ok_or(Error::InvalidMessage)?;
+    let digest = username_proof_digest(&proof.name, proof.timestamp, owner);
+    if &recover_address(&digest, &proof.signature)? == server {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
@@ -0,0 +1,119 @@
+//! Typed access to the protobuf enums stored as raw `i32` fields.
+//!
+//! Protobuf keeps enum fields open: a message may carry a value its schema does not name.
+//! The conversions here make that case explicit with [`UnknownEnum`].
+use crate::message::*;
+use crate::Error;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// An `i32` enum field holding a value the enum does not define.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct UnknownEnum(pub i32);
+
+impl From<UnknownEnum> for Error {
+    fn from(_: UnknownEnum) -> Self {
+        Error::InvalidMessage
+    }
+}
+
+macro_rules! impl_enum_conversions {
+    ($($name:ident { $($variant:ident),+ $(,)? })+) => {$(
+        impl TryFrom<i32> for $name {
+            type Error = UnknownEnum;
+
+            fn try_from(value: i32) -> Result<Self, UnknownEnum> {
+                $(
+                    if value == $name::$variant as i32 {
+                        return Ok($name::$variant);
+                    }
+                )+
+                Err(UnknownEnum(value))
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(value: $name) -> Self {
+                value as i32
+            }
+        }
+    )+};
+}
+
+impl_enum_conversions! {
+    HashScheme { None, Blake3 }
+    SignatureScheme { None, Ed25519, Eip712 }
+    MessageType {
+        None,
+        CastAdd,
+        CastRemove,
+        ReactionAdd,
+        ReactionRemove,
+        LinkAdd,
+        LinkRemove,
+        VerificationAddEthAddress,
+        VerificationRemove,
+        UserDataAdd,
+        UsernameProof,
+        FrameAction,
+        LinkCompactState,
+    }
+    FarcasterNetwork { None, Mainnet, Testnet, Devnet }
+    UserDataType { None, Pfp, Display, Bio, Url, Username }
+    CastType { Cast, LongCast }
+    ReactionType { None, Like, Recast }
+    Protocol { Ethereum, Solana }
+    UserNameType { UsernameTypeNone, UsernameTypeFname, UsernameTypeEnsL1 }
+}
+
+macro_rules! impl_enum_getters {
+    ($($message:ident { $($(#[$doc:meta])* $getter:ident($field:ident) -> $name:ident;)+ })+) => {$(
+        impl $message {
+            $(
+                $(#[$doc])*
+                pub fn $getter(&self) -> Result<$name, UnknownEnum> {
+                    $name::try_from(self.$field)
+                }
+            )+
+        }
+    )+};
+}
+
+impl_enum_getters! {
+    Message {
+        /// Returns `hash_scheme` as a [`HashScheme`].
+        hash_scheme(hash_scheme) -> HashScheme;
+        /// Returns `signature_scheme` as a [`SignatureScheme`].
+        signature_scheme(signature_scheme) -> SignatureScheme;
+    }
+    MessageData {
+        /// Returns `type` as a [`MessageType`].
+        message_type(r#type) -> MessageType;
+        /// Returns `network` as a [`FarcasterNetwork`].
+        network(network) -> FarcasterNetwork;
+    }
+    UserDataBody {
+        /// Returns `type` as a [`UserDataType`].
+        user_data_type(r#type) -> UserDataType;
+    }
+    CastAddBody {
+        /// Returns `type` as a [`CastType`].
+        cast_type(r#type) -> CastType;
+    }
+    ReactionBody {
+        /// Returns `type` as a [`ReactionType`].
+        reaction_type(r#type) -> ReactionType;
+    }
+    VerificationAddAddressBody {
+        /// Returns `protocol` as a [`Protocol`].
+        protocol(protocol) -> Protocol;
+    }
+    VerificationRemoveBody {
+        /// Returns `protocol` as a [`Protocol`].
+        protocol(protocol) -> Protocol;
+    }
+    UserNameProof {
+        /// Returns `type` as a [`UserNameType`].
+        username_type(r#type) -> UserNameType;
+    }
+}
@@ -0,0 +1,440 @@
+//! The frames whose actions this crate verifies, and their `fc:frame` HTML metadata.
+//!
+//! A frame is served as `<meta>` tags in the `<head>` of an HTML page, following the vNext
+//! frame specification. [`Frame::to_html`] renders them and [`Frame::from_html`] reads them
+//! back from a page. Buttons are numbered from 1 as in `FrameActionBody.button_index`, so
+//! `frame.button(action.button_index)` is the button a user pressed.
+extern crate alloc;
+use crate::frame_action::MAX_BUTTON_INDEX;
+use crate::limits::{
+    MAX_FRAME_INPUT_PLACEHOLDER_LENGTH, MAX_FRAME_STATE_LENGTH, MAX_FRAME_URL_LENGTH,
+};
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{borrow::ToOwned, format};
+
+/// The frame specification version this module reads and writes.
+pub const FRAME_VERSION: &str = "vNext";
+
+/// A frame, as described by its `fc:frame` meta tags.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Frame {
+    /// URL of the frame image.
+    pub image: String,
+    /// Aspect ratio of the image, `1.91:1` when not given.
+    pub aspect_ratio: Option<AspectRatio>,
+    /// Buttons in display order; `buttons[0]` has button index 1.
+    pub buttons: Vec<FrameButton>,
+    /// Placeholder of the text input, if the frame shows one.
+    pub input_text: Option<String>,
+    /// State passed back in `FrameActionBody.state`.
+    pub state: Option<String>,
+    /// URL actions are posted to, the frame URL when not given.
+    pub post_url: Option<String>,
+}
+
+/// Aspect ratio of a frame image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AspectRatio {
+    /// `1.91:1`.
+    Wide,
+    /// `1:1`.
+    Square,
+}
+
+/// A frame button.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameButton {
+    /// Text shown on the button.
+    pub label: String,
+    /// What pressing the button does.
+    pub action: ButtonAction,
+    /// Link, mint or transaction target; required by those actions.
+    pub target: Option<String>,
+    /// URL this button posts to instead of the frame's `post_url`.
+    pub post_url: Option<String>,
+}
+
+/// What pressing a frame button does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ButtonAction {
+    /// Posts a frame action and shows the frame returned.
+    #[default]
+    Post,
+    /// Posts a frame action and follows the redirect returned.
+    PostRedirect,
+    /// Opens `target`.
+    Link,
+    /// Mints the token `target` names.
+    Mint,
+    /// Posts a frame action to `target` to fetch a transaction to sign.
+    Tx,
+}
+
+impl AspectRatio {
+    /// Returns the value of `fc:frame:image:aspect_ratio`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AspectRatio::Wide => "1.91:1",
+            AspectRatio::Square => "1:1",
+        }
+    }
+}
+
+impl ButtonAction {
+    /// Returns the value of `fc:frame:button:$idx:action`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ButtonAction::Post => "post",
+            ButtonAction::PostRedirect => "post_redirect",
+            ButtonAction::Link => "link",
+            ButtonAction::Mint => "mint",
+            ButtonAction::Tx => "tx",
+        }
+    }
+
+    fn requires_target(&self) -> bool {
+        matches!(
+            self,
+            ButtonAction::Link | ButtonAction::Mint | ButtonAction::Tx
+        )
+    }
+}
+
+impl TryFrom<&str> for AspectRatio {
+    type Error = FrameError;
+
+    fn try_from(value: &str) -> Result<Self, FrameError> {
+        match value {
+            "1.91:1" => Ok(AspectRatio::Wide),
+            "1:1" => Ok(AspectRatio::Square),
+            _ => Err(FrameError::InvalidAspectRatio),
+        }
+    }
+}
+
+impl TryFrom<&str> for ButtonAction {
+    type Error = FrameError;
+
+    fn try_from(value: &str) -> Result<Self, FrameError> {
+        match value {
+            "post" => Ok(ButtonAction::Post),
+            "post_redirect" => Ok(ButtonAction::PostRedirect),
+            "link" => Ok(ButtonAction::Link),
+            "mint" => Ok(ButtonAction::Mint),
+            "tx" => Ok(ButtonAction::Tx),
+            _ => Err(FrameError::InvalidButtonAction),
+        }
+    }
+}
+
+/// Errors returned when rendering or parsing a [`Frame`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    /// `fc:frame` is missing or not `vNext`.
+    InvalidVersion,
+    /// `fc:frame:image` is missing or empty.
+    MissingImage,
+    /// `fc:frame:image:aspect_ratio` is neither `1.91:1` nor `1:1`.
+    InvalidAspectRatio,
+    /// The frame has more than four buttons.
+    TooManyButtons,
+    /// A button comes after a missing one, or has no label. Holds its button index.
+    MissingButton(u32),
+    /// `fc:frame:button:$idx:action` is not a known action.
+    InvalidButtonAction,
+    /// A link, mint or tx button has no target. Holds its button index.
+    MissingTarget(u32),
+    /// A post URL or button target is over `MAX_FRAME_URL_LENGTH`, the state over
+    /// `MAX_FRAME_STATE_LENGTH` or the input text over `MAX_FRAME_INPUT_PLACEHOLDER_LENGTH`.
+    TooLong,
+}
+
+impl Frame {
+    /// Returns the button with the given 1-based index, as in `FrameActionBody.button_index`.
+    pub fn button(&self, button_index: u32) -> Option<&FrameButton> {
+        let index = usize::try_from(button_index.checked_sub(1)?).ok()?;
+        self.buttons.get(index)
+    }
+
+    /// Checks the frame against the frame specification, including the length limits clients
+    /// enforce.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the frame can be rendered.
+    /// * `Err(FrameError)` describing the first problem found.
+    pub fn validate(&self) -> Result<(), FrameError> {
+        if self.image.is_empty() {
+            return Err(FrameError::MissingImage);
+        }
+        if self.buttons.len() > MAX_BUTTON_INDEX as usize {
+            return Err(FrameError::TooManyButtons);
+        }
+        check_length(&self.post_url, MAX_FRAME_URL_LENGTH)?;
+        check_length(&self.state, MAX_FRAME_STATE_LENGTH)?;
+        check_length(&self.input_text, MAX_FRAME_INPUT_PLACEHOLDER_LENGTH)?;
+        for (button_index, button) in (1..).zip(&self.buttons) {
+            if button.label.is_empty() {
+                return Err(FrameError::MissingButton(button_index));
+            }
+            if button.action.requires_target() && button.target.is_none() {
+                return Err(FrameError::MissingTarget(button_index));
+            }
+            check_length(&button.target, MAX_FRAME_URL_LENGTH)?;
+            check_length(&button.post_url, MAX_FRAME_URL_LENGTH)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the frame as `fc:frame` and `og:image` meta tags.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` holding one `<meta>` tag per line, for the `<head>` of the frame page.
+    /// * `Err(FrameError)` if the frame fails [`validate`](Self::validate).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::frame::{Frame, FrameButton};
+    ///
+    /// let frame = Frame {
+    ///     image: "https://frames.example.com/poll.png".into(),
+    ///     buttons: vec![FrameButton {
+    ///         label: "Yes".into(),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let html = frame.to_html().unwrap();
+    /// assert!(html.contains(r#"<meta property="fc:frame:button:1" content="Yes" />"#));
+    /// assert_eq!(Frame::from_html(&html), Ok(frame));
+    /// ```
+    pub fn to_html(&self) -> Result<String, FrameError> {
+        self.validate()?;
+        let mut tags = Vec::new();
+        tags.push(("fc:frame".to_owned(), FRAME_VERSION));
+        tags.push(("fc:frame:image".to_owned(), &*self.image));
+        tags.push(("og:image".to_owned(), &*self.image));
+        if let Some(aspect_ratio) = &self.aspect_ratio {
+            tags.push((
+                "fc:frame:image:aspect_ratio".to_owned(),
+                aspect_ratio.as_str(),
+            ));
+        }
+        for (button_index, button) in (1..).zip(&self.buttons) {
+            let prefix = format!("fc:frame:button:{button_index}");
+            tags.push((prefix.clone(), &button.label));
+            tags.push((format!("{prefix}:action"), button.action.as_str()));
+            if let Some(target) = &button.target {
+                tags.push((format!("{prefix}:target"), target));
+            }
+            if let Some(post_url) = &button.post_url {
+                tags.push((format!("{prefix}:post_url"), post_url));
+            }
+        }
+        if let Some(input_text) = &self.input_text {
+            tags.push(("fc:frame:input:text".to_owned(), input_text));
+        }
+        if let Some(state) = &self.state {
+            tags.push(("fc:frame:state".to_owned(), state));
+        }
+        if let Some(post_url) = &self.post_url {
+            tags.push(("fc:frame:post_url".to_owned(), post_url));
+        }
+        Ok(tags
+            .iter()
+            .map(|(property, content)| {
+                format!(
+                    "<meta property=\"{}\" content=\"{}\" />\n",
+                    escape(property),
+                    escape(content)
+                )
+            })
+            .collect())
+    }
+
+    /// Reads a frame from the `fc:frame` meta tags of an HTML page.
+    ///
+    /// Tags may name their key in `property` or `name`; the first tag for a key wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `html` - The frame page, or just its `<head>`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Frame)` if the page holds a valid vNext frame.
+    /// * `Err(FrameError)` describing the first problem found.
+    pub fn from_html(html: &str) -> Result<Frame, FrameError> {
+        let tags = meta_tags(html);
+        let get = |key: &str| tags.get(key).cloned();
+        if get("fc:frame").as_deref() != Some(FRAME_VERSION) {
+            return Err(FrameError::InvalidVersion);
+        }
+        let mut buttons = Vec::new();
+        for button_index in 1..=MAX_BUTTON_INDEX + 1 {
+            let prefix = format!("fc:frame:button:{button_index}");
+            let Some(label) = get(&prefix) else {
+                continue;
+            };
+            if button_index > MAX_BUTTON_INDEX {
+                return Err(FrameError::TooManyButtons);
+            }
+            if buttons.len() + 1 != button_index as usize {
+                return Err(FrameError::MissingButton(buttons.len() as u32 + 1));
+            }
+            let action = get(&format!("{prefix}:action"))
+                .map(|action| ButtonAction::try_from(&*action))
+                .transpose()?
+                .unwrap_or_default();
+            buttons.push(FrameButton {
+                label,
+                action,
+                target: get(&format!("{prefix}:target")),
+                post_url: get(&format!("{prefix}:post_url")),
+            });
+        }
+        let frame = Frame {
+            image: get("fc:frame:image").unwrap_or_default(),
+            aspect_ratio: get("fc:frame:image:aspect_ratio")
+                .map(|ratio| AspectRatio::try_from(&*ratio))
+                .transpose()?,
+            buttons,
+            input_text: get("fc:frame:input:text"),
+            state: get("fc:frame:state"),
+            post_url: get("fc:frame:post_url"),
+        };
+        frame.validate()?;
+        Ok(frame)
+    }
+}
+
+/// Fails with `FrameError::TooLong` if `value` is over `max` bytes.
+fn check_length(value: &Option<String>, max: u32) -> Result<(), FrameError> {
+    match value {
+        Some(value) if value.len() > max as usize => Err(FrameError::TooLong),
+        _ => Ok(()),
+    }
+}
+
+/// Escapes text for a double-quoted HTML attribute.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverts [`escape`], leaving unknown entities as they are.
+fn unescape(text: &str) -> String {
+    const ENTITIES: [(&str, &str); 7] = [
+        ("&amp;", "&"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&quot;", "\""),
+        ("&#39;", "'"),
+        ("&#x27;", "'"),
+        ("&apos;", "'"),
+    ];
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        unescaped.push_str(&rest[..at]);
+        rest = &rest[at..];
+        match ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+            Some((entity, c)) => {
+                unescaped.push_str(c);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Collects the `property` or `name` and `content` of every `<meta>` tag.
+fn meta_tags(html: &str) -> BTreeMap<String, String> {
+    let lower = html.to_ascii_lowercase();
+    let mut tags = BTreeMap::new();
+    let mut from = 0;
+    while let Some(at) = lower[from..].find("<meta") {
+        let start = from + at + "<meta".len();
+        let (attributes, end) = attributes(html, start);
+        from = end;
+        let key = attributes
+            .iter()
+            .find(|(name, _)| name == "property" || name == "name");
+        let content = attributes.iter().find(|(name, _)| name == "content");
+        if let (Some((_, key)), Some((_, content))) = (key, content) {
+            tags.entry(key.clone()).or_insert_with(|| content.clone());
+        }
+    }
+    tags
+}
+
+/// Reads the attributes of a tag starting at byte `at`, returning them with lowercase names
+/// and unescaped values, and the byte offset after the tag.
+fn attributes(html: &str, mut at: usize) -> (Vec<(String, String)>, usize) {
+    let bytes = html.as_bytes();
+    let mut attributes = Vec::new();
+    loop {
+        while at < bytes.len() && (bytes[at].is_ascii_whitespace() || bytes[at] == b'/') {
+            at += 1;
+        }
+        if at >= bytes.len() || bytes[at] == b'>' {
+            return (attributes, (at + 1).min(bytes.len()));
+        }
+        let name_start = at;
+        while at < bytes.len() && !matches!(bytes[at], b'=' | b'>' | b'/') {
+            if bytes[at].is_ascii_whitespace() {
+                break;
+            }
+            at += 1;
+        }
+        let name = html[name_start..at].to_ascii_lowercase();
+        while at < bytes.len() && bytes[at].is_ascii_whitespace() {
+            at += 1;
+        }
+        if at >= bytes.len() || bytes[at] != b'=' {
+            attributes.push((name, String::new()));
+            continue;
+        }
+        at += 1;
+        while at < bytes.len() && bytes[at].is_ascii_whitespace() {
+            at += 1;
+        }
+        let value = match bytes.get(at) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let value_start = at + 1;
+                let value_end = html[value_start..]
+                    .find(quote as char)
+                    .map_or(bytes.len(), |len| value_start + len);
+                at = (value_end + 1).min(bytes.len());
+                &html[value_start..value_end]
+            }
+            _ => {
+                let value_start = at;
+                while at < bytes.len() && !bytes[at].is_ascii_whitespace() && bytes[at] != b'>' {
+                    at += 1;
+                }
+                &html[value_start..at]
+            }
+        };
+        attributes.push((name, unescape(value)));
+    }
+}
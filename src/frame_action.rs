@@ -0,0 +1,236 @@
+//! Parsing, verification and validation of frame actions.
+extern crate alloc;
+use crate::borrowed::MessageRef;
+use crate::limits::{MAX_FRAME_INPUT_TEXT_LENGTH, MAX_FRAME_URL_LENGTH};
+use crate::message::*;
+use crate::time::FarcasterTime;
+use crate::validation::{check_network, check_timestamp, ValidationConfig};
+use crate::verification::HASH_LENGTH;
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+
+/// Highest `FrameActionBody.button_index`, as a frame has at most four buttons.
+pub const MAX_BUTTON_INDEX: u32 = 4;
+
+/// A frame action whose hash and signature have been verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedFrameAction {
+    /// Fid of the user who performed the action.
+    pub fid: u64,
+    /// URL of the frame the action was taken on.
+    pub url: String,
+    /// Index of the button pressed, starting at 1.
+    pub button_index: u32,
+    /// The cast that contained the frame, if any.
+    pub cast_id: Option<CastId>,
+    /// Text input from the user.
+    pub input_text: Vec<u8>,
+    /// Serialized frame state.
+    pub state: Vec<u8>,
+    /// Chain-specific transaction id for tx actions.
+    pub transaction_id: Vec<u8>,
+    /// Chain-specific address for tx actions.
+    pub address: Vec<u8>,
+    /// When the action was signed.
+    pub timestamp: FarcasterTime,
+    /// Network the action was signed for.
+    pub network: FarcasterNetwork,
+    /// `Message.hash` of the action.
+    pub hash: [u8; HASH_LENGTH],
+    /// Public key or address that signed the action.
+    pub signer: Vec<u8>,
+}
+
+/// Decodes a protobuf-encoded frame action and verifies it.
+///
+/// Every check of [`validate_message`](crate::validate_message), including
+/// [`validate_frame_action`], runs with `config`, except that only `MessageType::FrameAction`
+/// messages are accepted whatever `config.message_types` says.
+///
+/// The message is read through a [`MessageRef`]: the hash and signature are checked over the
+/// borrowed bytes, and only the fields of the returned action are copied.
+///
+/// # Arguments
+///
+/// * `raw` - The protobuf-encoded `Message`, e.g. `trustedData.messageBytes`.
+/// * `config` - The expectations to check the message against.
+///
+/// # Returns
+///
+/// * `Ok(VerifiedFrameAction)` if the message is a valid frame action.
+/// * `Err(Error)` describing the first check that failed.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{parse_frame_action, Error, ValidationConfig};
+///
+/// let result = parse_frame_action(&[0x0a, 0x00], &ValidationConfig::default());
+/// assert_eq!(result, Err(Error::InvalidFid));
+/// ```
+pub fn parse_frame_action(
+    raw: &[u8],
+    config: &ValidationConfig,
+) -> Result<VerifiedFrameAction, Error> {
+    // An empty buffer is a valid protobuf encoding of an empty `Message`, never a hub message.
+    if raw.is_empty() {
+        return Err(Error::InvalidProtobuf);
+    }
+    let msg = MessageRef::decode(raw)?;
+    let data = msg.message_data()?.ok_or(Error::MissingData)?;
+    if data.fid == 0 {
+        return Err(Error::InvalidFid);
+    }
+    let hash = msg.hash.try_into().map_err(|_| Error::InvalidHash)?;
+    msg.verify_hash()?;
+    msg.verify_signature()?;
+    let network = check_network(data.network, config.network)?;
+    check_timestamp(data.timestamp, config)?;
+
+    if data.body.is_none() {
+        return Err(Error::MissingBody);
+    }
+    if data.r#type != MessageType::FrameAction as i32 {
+        return Err(Error::UnexpectedMessageType);
+    }
+    let body = data.frame_action().ok_or(Error::BodyMismatch)?;
+    check_frame_action(
+        body.button_index,
+        body.url,
+        body.input_text,
+        config.frame_origins,
+    )?;
+    let url = core::str::from_utf8(body.url).map_err(|_| Error::InvalidUrl)?;
+    Ok(VerifiedFrameAction {
+        fid: data.fid,
+        url: url.into(),
+        button_index: body.button_index,
+        cast_id: body.cast_id.map(CastId::from),
+        input_text: body.input_text.to_vec(),
+        state: body.state.to_vec(),
+        transaction_id: body.transaction_id.to_vec(),
+        address: body.address.to_vec(),
+        timestamp: FarcasterTime(data.timestamp),
+        network,
+        hash,
+        signer: msg.signer.to_vec(),
+    })
+}
+
+/// Checks the fields of a `FrameActionBody` against what frames allow.
+///
+/// `button_index` must be between 1 and [`MAX_BUTTON_INDEX`], `url` an http or https URL of
+/// at most `MAX_FRAME_URL_LENGTH` bytes, and `input_text` at most
+/// `MAX_FRAME_INPUT_TEXT_LENGTH` bytes long.
+///
+/// # Arguments
+///
+/// * `body` - The `FrameActionBody` to check.
+/// * `origins` - Origins the URL must have, such as `https://frames.example.com`, or an empty
+///   slice to accept any origin. Scheme and host compare case-insensitively.
+///
+/// # Returns
+///
+/// * `Ok(())` if the body is valid.
+/// * `Err(Error::InvalidButtonIndex)` if `button_index` is out of range.
+/// * `Err(Error::TooLong)` if `url` or `input_text` is over its limit.
+/// * `Err(Error::InvalidUrl)` if `url` is not a UTF-8 http or https URL.
+/// * `Err(Error::UrlNotAllowed)` if the origin of `url` is not in `origins`.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::frame_action::validate_frame_action;
+/// use pallet_farcaster_frame::{message::FrameActionBody, Error};
+///
+/// let body = FrameActionBody {
+///     url: b"https://frames.example.com/vote".to_vec(),
+///     button_index: 2,
+///     ..Default::default()
+/// };
+/// assert_eq!(validate_frame_action(&body, &["https://frames.example.com"]), Ok(()));
+/// assert_eq!(
+///     validate_frame_action(&body, &["https://other.example.com"]),
+///     Err(Error::UrlNotAllowed)
+/// );
+/// ```
+pub fn validate_frame_action(body: &FrameActionBody, origins: &[&str]) -> Result<(), Error> {
+    check_frame_action(body.button_index, &body.url, &body.input_text, origins)
+}
+
+/// [`validate_frame_action`] over the checked fields, so borrowed bodies can be checked.
+fn check_frame_action(
+    button_index: u32,
+    url: &[u8],
+    input_text: &[u8],
+    origins: &[&str],
+) -> Result<(), Error> {
+    if !(1..=MAX_BUTTON_INDEX).contains(&button_index) {
+        return Err(Error::InvalidButtonIndex);
+    }
+    if url.len() > MAX_FRAME_URL_LENGTH as usize
+        || input_text.len() > MAX_FRAME_INPUT_TEXT_LENGTH as usize
+    {
+        return Err(Error::TooLong);
+    }
+    let url = core::str::from_utf8(url).map_err(|_| Error::InvalidUrl)?;
+    let origin = url_origin(url).ok_or(Error::InvalidUrl)?;
+    if !origins.is_empty() && !origins.iter().any(|o| o.eq_ignore_ascii_case(origin)) {
+        return Err(Error::UrlNotAllowed);
+    }
+    Ok(())
+}
+
+/// Returns whether `url` is the frame URL `prefix` or lies under it.
+///
+/// The origins must be equal, compared case-insensitively, and the path of `url` must continue
+/// the path of `prefix` at a segment boundary, so `https://mint.example.com` matches neither
+/// `https://mint.example.com.evil/` nor `https://mint.example.community/`.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::frame_action::url_has_prefix;
+///
+/// assert!(url_has_prefix("https://mint.example.com/nft/1", "https://mint.example.com/nft"));
+/// assert!(!url_has_prefix("https://mint.example.com/nfts", "https://mint.example.com/nft"));
+/// assert!(!url_has_prefix("https://mint.example.com.evil/", "https://mint.example.com"));
+/// ```
+pub fn url_has_prefix(url: &str, prefix: &str) -> bool {
+    let (Some(origin), Some(prefix_origin)) = (url_origin(url), url_origin(prefix)) else {
+        return false;
+    };
+    if !origin.eq_ignore_ascii_case(prefix_origin) {
+        return false;
+    }
+    let prefix_path = &prefix[prefix_origin.len()..];
+    match url[origin.len()..].strip_prefix(prefix_path) {
+        Some(rest) => {
+            prefix_path.is_empty()
+                || prefix_path.ends_with('/')
+                || rest.is_empty()
+                || rest.starts_with(['/', '?', '#'])
+        }
+        None => false,
+    }
+}
+
+/// Returns the `scheme://host[:port]` prefix of an http or https URL.
+fn url_origin(url: &str) -> Option<&str> {
+    if url
+        .bytes()
+        .any(|b| b.is_ascii_whitespace() || b.is_ascii_control())
+    {
+        return None;
+    }
+    let authority = ["https://", "http://"].iter().find_map(|scheme| {
+        url.get(..scheme.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            .map(|_| &url[scheme.len()..])
+    })?;
+    let len = authority.find(['/', '?', '#']).unwrap_or(authority.len());
+    if len == 0 {
+        return None;
+    }
+    Some(&url[..url.len() - authority.len() + len])
+}
@@ -0,0 +1,253 @@
+//! Parsing of the JSON body a frame server receives when a user presses a button.
+//!
+//! The body carries the signed frame action twice: as hex protobuf bytes in
+//! `trustedData.messageBytes`, and as plain JSON in `untrustedData` for servers that do not
+//! verify messages. [`parse_frame_request`] verifies the former and reports every field of
+//! the latter that disagrees with it.
+//!
+//! Available with the `serde` feature.
+use crate::frame_action::{parse_frame_action, VerifiedFrameAction};
+use crate::time::FarcasterTime;
+use crate::validation::ValidationConfig;
+use crate::Error;
+use serde::Deserialize;
+
+/// The JSON body of a frame action POST request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameRequest {
+    /// Fields of the action as reported by the client, unverified.
+    pub untrusted_data: UntrustedData,
+    /// The signed action.
+    pub trusted_data: TrustedData,
+}
+
+/// The unverified `untrustedData` of a [`FrameRequest`].
+///
+/// Optional fields are only checked when present.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UntrustedData {
+    /// Fid of the user who performed the action.
+    pub fid: u64,
+    /// URL of the frame the action was taken on.
+    pub url: String,
+    /// `Message.hash` as `0x`-prefixed hex.
+    pub message_hash: String,
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    /// The `FarcasterNetwork` the action was sent to.
+    pub network: i32,
+    /// Index of the button pressed, starting at 1.
+    pub button_index: u32,
+    /// Text input from the user.
+    pub input_text: Option<String>,
+    /// The cast that contained the frame.
+    pub cast_id: Option<UntrustedCastId>,
+    /// Serialized frame state.
+    pub state: Option<String>,
+    /// Transaction id as `0x`-prefixed hex.
+    pub transaction_id: Option<String>,
+    /// Address as `0x`-prefixed hex.
+    pub address: Option<String>,
+}
+
+/// The `castId` of [`UntrustedData`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct UntrustedCastId {
+    /// Fid of the user who created the cast.
+    pub fid: u64,
+    /// Cast hash as `0x`-prefixed hex.
+    pub hash: String,
+}
+
+/// The `trustedData` of a [`FrameRequest`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedData {
+    /// The protobuf-encoded `Message` as hex.
+    pub message_bytes: String,
+}
+
+/// A field of [`UntrustedData`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UntrustedField {
+    /// `fid`.
+    Fid,
+    /// `url`.
+    Url,
+    /// `messageHash`.
+    MessageHash,
+    /// `timestamp`, compared to the second.
+    Timestamp,
+    /// `network`.
+    Network,
+    /// `buttonIndex`.
+    ButtonIndex,
+    /// `inputText`.
+    InputText,
+    /// `castId`, its fid or hash.
+    CastId,
+    /// `state`.
+    State,
+    /// `transactionId`.
+    TransactionId,
+    /// `address`.
+    Address,
+}
+
+/// Errors returned when parsing a [`FrameRequest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrameRequestError {
+    /// The body is not a frame request.
+    InvalidJson,
+    /// `trustedData.messageBytes` is not hex.
+    InvalidMessageBytes,
+    /// The signed message failed to parse or verify.
+    Message(Error),
+    /// `untrustedData` disagrees with the signed message in these fields.
+    Mismatch(Vec<UntrustedField>),
+}
+
+impl From<Error> for FrameRequestError {
+    fn from(error: Error) -> Self {
+        FrameRequestError::Message(error)
+    }
+}
+
+impl FrameRequest {
+    /// Deserializes a frame request from its JSON body.
+    pub fn from_json(json: &[u8]) -> Result<Self, FrameRequestError> {
+        serde_json::from_slice(json).map_err(|_| FrameRequestError::InvalidJson)
+    }
+
+    /// Verifies `trustedData` and checks `untrustedData` against it.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The expectations to check the signed message against.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VerifiedFrameAction)` if the message is valid and `untrustedData` agrees with it.
+    /// * `Err(FrameRequestError)` otherwise.
+    pub fn verify(
+        &self,
+        config: &ValidationConfig,
+    ) -> Result<VerifiedFrameAction, FrameRequestError> {
+        let raw = decode_hex(&self.trusted_data.message_bytes)
+            .ok_or(FrameRequestError::InvalidMessageBytes)?;
+        let action = parse_frame_action(&raw, config)?;
+        let mismatches = self.untrusted_data.mismatches(&action);
+        if mismatches.is_empty() {
+            Ok(action)
+        } else {
+            Err(FrameRequestError::Mismatch(mismatches))
+        }
+    }
+}
+
+impl UntrustedData {
+    /// Returns every field that disagrees with `action`.
+    pub fn mismatches(&self, action: &VerifiedFrameAction) -> Vec<UntrustedField> {
+        let same_hex = |hex: &str, bytes: &[u8]| decode_hex(hex).as_deref() == Some(bytes);
+        let checks = [
+            (UntrustedField::Fid, self.fid == action.fid),
+            (UntrustedField::Url, self.url == action.url),
+            (
+                UntrustedField::MessageHash,
+                same_hex(&self.message_hash, &action.hash),
+            ),
+            (
+                UntrustedField::Timestamp,
+                FarcasterTime::from_unix_millis(self.timestamp) == Ok(action.timestamp),
+            ),
+            (
+                UntrustedField::Network,
+                self.network == i32::from(action.network),
+            ),
+            (
+                UntrustedField::ButtonIndex,
+                self.button_index == action.button_index,
+            ),
+            (
+                UntrustedField::InputText,
+                self.input_text
+                    .as_ref()
+                    .is_none_or(|text| text.as_bytes() == action.input_text),
+            ),
+            (
+                UntrustedField::CastId,
+                self.cast_id.as_ref().is_none_or(|cast_id| {
+                    action.cast_id.as_ref().is_some_and(|verified| {
+                        cast_id.fid == verified.fid && same_hex(&cast_id.hash, &verified.hash)
+                    })
+                }),
+            ),
+            (
+                UntrustedField::State,
+                self.state
+                    .as_ref()
+                    .is_none_or(|state| state.as_bytes() == action.state),
+            ),
+            (
+                UntrustedField::TransactionId,
+                self.transaction_id
+                    .as_ref()
+                    .is_none_or(|id| same_hex(id, &action.transaction_id)),
+            ),
+            (
+                UntrustedField::Address,
+                self.address
+                    .as_ref()
+                    .is_none_or(|address| same_hex(address, &action.address)),
+            ),
+        ];
+        checks
+            .into_iter()
+            .filter(|(_, agrees)| !agrees)
+            .map(|(field, _)| field)
+            .collect()
+    }
+}
+
+/// Parses and verifies the JSON body of a frame action POST request.
+///
+/// `trustedData.messageBytes` is verified with [`parse_frame_action`], then every field of
+/// `untrustedData` is compared with the verified action. `untrustedData.timestamp` is in
+/// Unix milliseconds and only has to fall within the second of the message timestamp.
+///
+/// # Arguments
+///
+/// * `json` - The request body.
+/// * `config` - The expectations to check the signed message against.
+///
+/// # Returns
+///
+/// * `Ok(VerifiedFrameAction)` if the message is valid and `untrustedData` agrees with it.
+/// * `Err(FrameRequestError::InvalidJson)` if the body is not a frame request.
+/// * `Err(FrameRequestError::InvalidMessageBytes)` if `messageBytes` is not hex.
+/// * `Err(FrameRequestError::Message(Error))` if the message fails to parse or verify.
+/// * `Err(FrameRequestError::Mismatch(fields))` listing every field of `untrustedData` that
+///   disagrees with the message.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::frame_request::{parse_frame_request, FrameRequestError};
+/// use pallet_farcaster_frame::ValidationConfig;
+///
+/// let result = parse_frame_request(b"{}", &ValidationConfig::default());
+/// assert_eq!(result, Err(FrameRequestError::InvalidJson));
+/// ```
+pub fn parse_frame_request(
+    json: &[u8],
+    config: &ValidationConfig,
+) -> Result<VerifiedFrameAction, FrameRequestError> {
+    FrameRequest::from_json(json)?.verify(config)
+}
+
+/// Decodes hex with an optional `0x` prefix.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).ok()
+}
@@ -0,0 +1,271 @@
+//! Typed state carried through a frame in `FrameActionBody.state`.
+//!
+//! A frame server puts its state in the `fc:frame:state` meta tag and gets it back, unchanged
+//! by honest clients, in the state of the next frame action. [`FrameState`] SCALE-encodes a
+//! typed value into that tag as hex, or with the `serde` feature writes it as JSON, and can
+//! append a keyed blake3 MAC so state altered by the client is rejected. [`FrameState::bind`]
+//! ties the MAC to a context such as the fid and frame URL, so state cannot be replayed by
+//! another user or on another frame.
+extern crate alloc;
+use crate::limits::MAX_FRAME_STATE_LENGTH;
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+use parity_scale_codec::{DecodeAll, Encode};
+
+/// Length in bytes of the MAC appended to authenticated state.
+pub const STATE_MAC_LENGTH: usize = blake3::OUT_LEN;
+
+/// Errors returned when encoding or decoding a [`FrameState`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StateError {
+    /// The encoded state is longer than `MAX_FRAME_STATE_LENGTH`.
+    TooLong,
+    /// The state, or the MAC of JSON state, is not hex.
+    InvalidEncoding,
+    /// The state is shorter than its MAC or the MAC does not match.
+    InvalidMac,
+    /// The state does not decode to the expected type.
+    InvalidState,
+}
+
+/// Encodes and decodes frame state of type `T`.
+pub struct FrameState<T> {
+    key: Option<[u8; 32]>,
+    _state: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for FrameState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FrameState<T> {}
+
+impl<T> Default for FrameState<T> {
+    fn default() -> Self {
+        FrameState::new()
+    }
+}
+
+impl<T> FrameState<T> {
+    /// Creates a codec for unauthenticated state.
+    pub const fn new() -> Self {
+        FrameState {
+            key: None,
+            _state: PhantomData,
+        }
+    }
+
+    /// Creates a codec that authenticates state with a MAC under `key`.
+    ///
+    /// The key must stay secret to the frame server.
+    pub const fn with_key(key: [u8; 32]) -> Self {
+        FrameState {
+            key: Some(key),
+            _state: PhantomData,
+        }
+    }
+
+    /// Binds the MAC to `context`, so state only decodes under the same context.
+    ///
+    /// The codec's key is replaced by one derived from the key and every part of `context`.
+    /// Binding a codec without a key has no effect.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The values the state belongs to, such as the fid and frame URL.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::frame_state::{FrameState, StateError};
+    ///
+    /// let codec = FrameState::<u32>::with_key([7; 32]);
+    /// let url = "https://frames.example.com/vote";
+    /// let alice = codec.bind(&[&2u64.to_le_bytes(), url.as_bytes()]);
+    /// let bob = codec.bind(&[&3u64.to_le_bytes(), url.as_bytes()]);
+    /// let encoded = alice.encode(&1).unwrap();
+    /// assert_eq!(alice.decode(encoded.as_bytes()), Ok(1));
+    /// assert_eq!(bob.decode(encoded.as_bytes()), Err(StateError::InvalidMac));
+    /// ```
+    pub fn bind(self, context: &[&[u8]]) -> Self {
+        let key = self.key.map(|key| {
+            // Length prefixes keep `["ab", "c"]` and `["a", "bc"]` apart.
+            let mut hasher = blake3::Hasher::new_keyed(&key);
+            for part in context {
+                hasher.update(&(part.len() as u64).to_le_bytes());
+                hasher.update(part);
+            }
+            *hasher.finalize().as_bytes()
+        });
+        FrameState {
+            key,
+            _state: PhantomData,
+        }
+    }
+
+    fn mac(&self, payload: &[u8]) -> Option<blake3::Hash> {
+        self.key.map(|key| blake3::keyed_hash(&key, payload))
+    }
+
+    fn check_mac(&self, payload: &[u8], mac: &[u8]) -> Result<(), StateError> {
+        let mac: [u8; STATE_MAC_LENGTH] = mac.try_into().map_err(|_| StateError::InvalidMac)?;
+        // `blake3::Hash` compares in constant time.
+        if self.mac(payload) != Some(blake3::Hash::from(mac)) {
+            return Err(StateError::InvalidMac);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Encode + DecodeAll> FrameState<T> {
+    /// Encodes `state` for the `fc:frame:state` meta tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to encode.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` holding the hex SCALE encoding of `state`, followed by its MAC when the
+    ///   codec has a key.
+    /// * `Err(StateError::TooLong)` if the result is over `MAX_FRAME_STATE_LENGTH` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::frame_state::FrameState;
+    ///
+    /// let codec = FrameState::<(u32, bool)>::with_key([7; 32]);
+    /// let encoded = codec.encode(&(3, true)).unwrap();
+    /// assert_eq!(codec.decode(encoded.as_bytes()), Ok((3, true)));
+    /// ```
+    pub fn encode(&self, state: &T) -> Result<String, StateError> {
+        let mut bytes = state.encode();
+        if let Some(mac) = self.mac(&bytes) {
+            bytes.extend_from_slice(mac.as_bytes());
+        }
+        if bytes.len() * 2 > MAX_FRAME_STATE_LENGTH as usize {
+            return Err(StateError::TooLong);
+        }
+        Ok(encode_hex(&bytes))
+    }
+
+    /// Decodes state returned in `FrameActionBody.state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The state bytes of a frame action.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(T)` if the state decodes and, when the codec has a key, its MAC matches.
+    /// * `Err(StateError)` describing the first check that failed.
+    pub fn decode(&self, bytes: &[u8]) -> Result<T, StateError> {
+        if bytes.len() > MAX_FRAME_STATE_LENGTH as usize {
+            return Err(StateError::TooLong);
+        }
+        let bytes = decode_hex(bytes).ok_or(StateError::InvalidEncoding)?;
+        let payload = match self.key {
+            Some(_) => {
+                let split = bytes
+                    .len()
+                    .checked_sub(STATE_MAC_LENGTH)
+                    .ok_or(StateError::InvalidMac)?;
+                let (payload, mac) = bytes.split_at(split);
+                self.check_mac(payload, mac)?;
+                payload
+            }
+            None => &bytes[..],
+        };
+        T::decode_all(&mut &payload[..]).map_err(|_| StateError::InvalidState)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> FrameState<T> {
+    /// Encodes `state` as JSON for the `fc:frame:state` meta tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to encode.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` holding the JSON of `state`, followed by its MAC as hex when the codec
+    ///   has a key.
+    /// * `Err(StateError::InvalidState)` if `state` does not serialize to JSON.
+    /// * `Err(StateError::TooLong)` if the result is over `MAX_FRAME_STATE_LENGTH` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::frame_state::FrameState;
+    ///
+    /// let codec = FrameState::<Vec<u32>>::new();
+    /// let encoded = codec.encode_json(&vec![3, 1]).unwrap();
+    /// assert_eq!(encoded, "[3,1]");
+    /// assert_eq!(codec.decode_json(encoded.as_bytes()), Ok(vec![3, 1]));
+    /// ```
+    pub fn encode_json(&self, state: &T) -> Result<String, StateError> {
+        let mut json = serde_json::to_string(state).map_err(|_| StateError::InvalidState)?;
+        if let Some(mac) = self.mac(json.as_bytes()) {
+            json.push_str(&encode_hex(mac.as_bytes()));
+        }
+        if json.len() > MAX_FRAME_STATE_LENGTH as usize {
+            return Err(StateError::TooLong);
+        }
+        Ok(json)
+    }
+
+    /// Decodes JSON state returned in `FrameActionBody.state`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The state bytes of a frame action.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(T)` if the state deserializes and, when the codec has a key, its MAC matches.
+    /// * `Err(StateError)` describing the first check that failed.
+    pub fn decode_json(&self, bytes: &[u8]) -> Result<T, StateError> {
+        if bytes.len() > MAX_FRAME_STATE_LENGTH as usize {
+            return Err(StateError::TooLong);
+        }
+        let json = match self.key {
+            Some(_) => {
+                let split = bytes
+                    .len()
+                    .checked_sub(STATE_MAC_LENGTH * 2)
+                    .ok_or(StateError::InvalidMac)?;
+                let (json, mac) = bytes.split_at(split);
+                let mac = decode_hex(mac).ok_or(StateError::InvalidEncoding)?;
+                self.check_mac(json, &mac)?;
+                json
+            }
+            None => bytes,
+        };
+        serde_json::from_slice(json).map_err(|_| StateError::InvalidState)
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[usize::from(byte >> 4)] as char);
+        hex.push(DIGITS[usize::from(byte & 0x0f)] as char);
+    }
+    hex
+}
+
+fn decode_hex(hex: &[u8]) -> Option<Vec<u8>> {
+    let digit = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    hex.chunks(2)
+        .map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?))
+        .collect()
+}
@@ -0,0 +1,171 @@
+//! JSON in the shape of the hub HTTP API, behind the `serde` feature.
+//!
+//! Hubs write messages as protobuf JSON: camelCase field names, enum values by name such as
+//! `MESSAGE_TYPE_FRAME_ACTION`, and a `oneof` as the key of its set variant, such as
+//! `frameActionBody` inside `data`. Hashes, signers and addresses are `0x`-prefixed hex; all
+//! other bytes are base64. `build.rs` points the serde derives of the generated types at the
+//! helpers in this module, so `serde_json` reads and writes that shape directly.
+//!
+//! Fields left out of the JSON take their protobuf defaults, and enum fields also accept the
+//! numeric value.
+use crate::enums::UnknownEnum;
+use crate::message::*;
+use crate::Error;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl Message {
+    /// Deserializes a message from hub JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - A message as returned by the hub HTTP API.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Message)` if the JSON is a message.
+    /// * `Err(Error::InvalidMessage)` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::message::{Message, MessageType};
+    ///
+    /// let json = r#"{
+    ///     "data": {
+    ///         "type": "MESSAGE_TYPE_FRAME_ACTION",
+    ///         "fid": 2,
+    ///         "timestamp": 99825232,
+    ///         "network": "FARCASTER_NETWORK_MAINNET",
+    ///         "frameActionBody": { "url": "aHR0cHM6Ly9leGFtcGxlLmNvbQ==", "buttonIndex": 1 }
+    ///     },
+    ///     "hash": "0x6357261fa893e4be85f78178babaca876f9a1fac",
+    ///     "hashScheme": "HASH_SCHEME_BLAKE3"
+    /// }"#;
+    /// let msg = Message::from_json(json).unwrap();
+    /// let data = msg.data.as_ref().unwrap();
+    /// assert_eq!(data.message_type(), Ok(MessageType::FrameAction));
+    /// assert_eq!(Message::from_json(&msg.to_json()), Ok(msg));
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|_| Error::InvalidMessage)
+    }
+
+    /// Serializes the message to hub JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("messages always serialize")
+    }
+}
+
+/// An enum field as written by hubs, or by its numeric value.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NameOrValue {
+    Name(String),
+    Value(i32),
+}
+
+macro_rules! impl_enum_json {
+    ($($module:ident: $name:ident),+ $(,)?) => {$(
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str_name())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $name::try_from($module::deserialize(deserializer)?).map_err(|UnknownEnum(value)| {
+                    D::Error::custom(format!("unknown {} {value}", stringify!($name)))
+                })
+            }
+        }
+
+        /// Serializes an `i32` field holding a
+        #[doc = concat!("[`", stringify!($name), "`]")]
+        /// by name, or by value if the enum does not define it.
+        pub(crate) mod $module {
+            use super::*;
+
+            pub fn serialize<S: Serializer>(value: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+                match $name::try_from(*value) {
+                    Ok(value) => value.serialize(serializer),
+                    Err(_) => serializer.serialize_i32(*value),
+                }
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+                match NameOrValue::deserialize(deserializer)? {
+                    NameOrValue::Value(value) => Ok(value),
+                    NameOrValue::Name(name) => $name::from_str_name(&name)
+                        .map(i32::from)
+                        .ok_or_else(|| {
+                            D::Error::custom(format!("unknown {} `{name}`", stringify!($name)))
+                        }),
+                }
+            }
+        }
+    )+};
+}
+
+impl_enum_json! {
+    hash_scheme: HashScheme,
+    signature_scheme: SignatureScheme,
+    message_type: MessageType,
+    farcaster_network: FarcasterNetwork,
+    user_data_type: UserDataType,
+    cast_type: CastType,
+    reaction_type: ReactionType,
+    protocol: Protocol,
+    user_name_type: UserNameType,
+}
+
+/// Serializes bytes as `0x`-prefixed hex.
+pub(crate) mod hex_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex::decode(hex.strip_prefix("0x").unwrap_or(&hex)).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes bytes as standard, padded base64.
+pub(crate) mod base64_bytes {
+    use super::*;
+    use base64::prelude::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64_STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let base64 = String::deserialize(deserializer)?;
+        BASE64_STANDARD.decode(base64).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes optional bytes as base64, as [`base64_bytes`] does.
+pub(crate) mod optional_base64_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        bytes: &Option<Vec<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => base64_bytes::serialize(bytes, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<u8>>, D::Error> {
+        base64_bytes::deserialize(deserializer).map(Some)
+    }
+}
@@ -1,30 +1,115 @@
 #![cfg_attr(not(feature = "std"), no_std)]
-// pub use pallet::*;
 
+#[cfg(all(test, feature = "pallet"))]
+mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod borrowed;
+pub mod bounded;
+pub mod builder;
+pub mod eip712;
+pub mod enums;
+pub mod frame;
+pub mod frame_action;
+#[cfg(feature = "serde")]
+pub mod frame_request;
+pub mod frame_state;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod limits;
 pub mod message;
+#[cfg(feature = "pallet")]
+pub mod pallet;
+pub mod protobuf;
+pub mod time;
+pub mod transaction;
+pub mod validation;
+pub mod verification;
+pub use enums::UnknownEnum;
+pub use frame_action::{parse_frame_action, VerifiedFrameAction};
 use message::*;
 use parity_scale_codec::{Decode, Encode};
+use protobuf::ProtoMessage;
+use scale_info::TypeInfo;
 use sp_std::vec::Vec;
+pub use time::FarcasterTime;
+pub use validation::{validate_message, ValidationConfig};
+pub use verification::{compute_hash, verify_hash, verify_message, verify_signature};
 
-#[derive(Debug, PartialEq)]
+/// Errors returned when parsing, verifying or validating a Farcaster message.
+///
+/// The enum is SCALE encodable so pallets can surface it in dispatch errors and events.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
 pub enum Error {
+    /// The bytes are not a valid protobuf message.
     InvalidProtobuf,
+    /// The message is malformed or cannot be encoded.
     InvalidMessage,
+    /// The message carries neither `data` nor `data_bytes`.
+    MissingData,
+    /// `MessageData.fid` is zero.
+    InvalidFid,
+    /// `hash` is not a 20 byte digest.
+    InvalidHash,
+    /// `hash_scheme` is not a scheme this crate can verify.
+    UnsupportedHashScheme,
+    /// `hash` is not the digest of the message data.
+    HashMismatch,
+    /// `signature_scheme` is not a scheme this crate can verify.
+    UnsupportedSignatureScheme,
+    /// `signer` is not a valid public key for the signature scheme.
+    InvalidSigner,
+    /// `signature` is malformed or was not produced by `signer` over `hash`.
+    InvalidSignature,
+    /// The verification or proof uses a protocol or type this crate cannot check.
+    UnsupportedVerification,
+    /// `MessageData.network` is not the network the caller expects.
+    WrongNetwork,
+    /// `MessageData.timestamp` is further in the future than the allowed drift.
+    TimestampInFuture,
+    /// `MessageData.body` is missing.
+    MissingBody,
+    /// `MessageData.type` is not one of the types the caller accepts.
+    UnexpectedMessageType,
+    /// `MessageData.timestamp` is older than the allowed age.
+    TimestampTooOld,
+    /// A time is before the Farcaster epoch or does not fit in Farcaster time.
+    InvalidTimestamp,
+    /// `MessageData.network` is `FarcasterNetwork::None` or not a known network.
+    InvalidNetwork,
+    /// `MessageData.body` is not the body variant `MessageData.type` calls for.
+    BodyMismatch,
+    /// A message or one of its fields is longer than its bound.
+    TooLong,
+    /// A URL is not valid UTF-8 or not an http or https URL.
+    InvalidUrl,
+    /// `FrameActionBody.button_index` is not between 1 and 4.
+    InvalidButtonIndex,
+    /// The origin of a frame URL is not one the caller allows.
+    UrlNotAllowed,
+    /// An address is not a 20 byte EVM address.
+    InvalidAddress,
+    /// A transaction id is not a 32 byte transaction hash.
+    InvalidTransactionId,
+    /// A chain id is not a CAIP-2 chain id, or not one of a supported namespace.
+    InvalidChainId,
 }
 
-/// Decodes a SCALE-encoded `Message` from a raw byte vector.
+/// Decodes a protobuf-encoded `Message` from a raw byte vector.
+///
+/// This is the format hubs and clients use, e.g. the `trustedData.messageBytes` a frame
+/// server receives.
 ///
 /// # Arguments
 ///
-/// * `raw` - A `Vec<u8>` containing the SCALE-encoded message.
+/// * `raw` - A `Vec<u8>` containing the protobuf-encoded message.
 ///
 /// # Returns
 ///
 /// * `Ok(Message)` if decoding is successful.
-/// * `Err(Error::InvalidProtobuf)` if decoding fails due to an invalid byte sequence.
+/// * `Err(Error::InvalidProtobuf)` if the input is empty or is not a valid protobuf message.
+/// * `Err(Error::TooLong)` if a field is over its hub [`limits`](crate::limits).
 ///
 /// # Example
 ///
@@ -32,15 +117,119 @@ pub enum Error {
 /// use pallet_farcaster_frame::{parse_message, Error};
 /// use sp_std::vec;
 ///
-/// let invalid_bytes = vec![];
+/// // Field 1 claims 5 bytes of `MessageData` but none follow.
+/// let invalid_bytes = vec![0x0a, 0x05];
 /// let result = parse_message(invalid_bytes);
 /// assert!(result.is_err());
 /// ```
 pub fn parse_message(raw: Vec<u8>) -> Result<Message, Error> {
-    let msg = Message::decode(&mut &*raw).map_err(|_| Error::InvalidProtobuf)?;
+    // An empty buffer is a valid protobuf encoding of an empty `Message`, never a hub message.
+    if raw.is_empty() {
+        return Err(Error::InvalidProtobuf);
+    }
+    let msg = Message::decode_proto(&raw)?;
+    Ok(msg)
+}
+
+/// Decodes a protobuf-encoded `Message` and checks it is intended for `network`.
+///
+/// Only the network is checked; use [`validate_message`] to verify the rest of the message.
+///
+/// # Arguments
+///
+/// * `raw` - A `Vec<u8>` containing the protobuf-encoded message.
+/// * `network` - The `FarcasterNetwork` the message must be intended for.
+///
+/// # Returns
+///
+/// * `Ok(Message)` if decoding is successful and the message is for `network`.
+/// * `Err(Error::InvalidProtobuf)` if the input is not a valid protobuf message.
+/// * `Err(Error::MissingData)` if the message carries no `MessageData`.
+/// * `Err(Error::InvalidNetwork)` if the message names no known network.
+/// * `Err(Error::WrongNetwork)` if the message is for another network.
+pub fn parse_message_for_network(
+    raw: Vec<u8>,
+    network: FarcasterNetwork,
+) -> Result<Message, Error> {
+    let msg = parse_message(raw)?;
+    match (&msg.data_bytes, &msg.data) {
+        (Some(data_bytes), _) => {
+            let data = MessageData::decode_proto(data_bytes)?;
+            validation::validate_network(&data, Some(network))?;
+        }
+        (None, Some(data)) => validation::validate_network(data, Some(network))?,
+        (None, None) => return Err(Error::MissingData),
+    }
     Ok(msg)
 }
 
+/// Decodes a SCALE-encoded `Message` from a raw byte vector.
+///
+/// # Arguments
+///
+/// * `raw` - A `Vec<u8>` containing the SCALE-encoded message.
+///
+/// # Returns
+///
+/// * `Ok(Message)` if decoding is successful.
+/// * `Err(Error::InvalidMessage)` if decoding fails due to an invalid byte sequence.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{parse_scale_message, Error};
+/// use sp_std::vec;
+///
+/// let invalid_bytes = vec![];
+/// let result = parse_scale_message(invalid_bytes);
+/// assert!(result.is_err());
+/// ```
+pub fn parse_scale_message(raw: Vec<u8>) -> Result<Message, Error> {
+    let msg = Message::decode(&mut &*raw).map_err(|_| Error::InvalidMessage)?;
+    Ok(msg)
+}
+
+/// Encodes a `Message` into a protobuf-encoded byte vector.
+///
+/// The output matches the bytes a hub produces for the same message, so a message parsed
+/// with [`parse_message`] encodes back to its original bytes.
+///
+/// # Arguments
+///
+/// * `msg` - A reference to a `Message` struct to be encoded.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` containing the protobuf-encoded data if encoding is successful.
+/// * `Err(Error::InvalidMessage)` if every field holds its default value, which encodes to
+///   an empty byte vector.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{encode_proto_message, message::Message};
+///
+/// let msg = Message {
+///     data: None,               // No valid data
+///     hash: vec![0x63; 20],     // Hash digest of the data
+///     hash_scheme: 1,           // Blake3
+///     signature: Vec::new(),    // Empty signature
+///     signature_scheme: 0,      // Invalid scheme
+///     signer: Vec::new(),       // No signer info
+///     data_bytes: None,         // No raw data
+///  };
+/// let encoded = encode_proto_message(&msg);
+/// assert!(encoded.is_ok());
+/// ```
+pub fn encode_proto_message(msg: &Message) -> Result<Vec<u8>, Error> {
+    let encoded = msg.encode_proto();
+    if encoded.is_empty() {
+        Err(Error::InvalidMessage)
+    } else {
+        Ok(encoded)
+    }
+}
+
 /// Encodes a `Message` into a SCALE-encoded byte vector.
 ///
 /// # Arguments
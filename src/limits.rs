@@ -0,0 +1,143 @@
+//! Size limits hubs enforce on message fields.
+//!
+//! Protobuf decoding rejects fields over the limits below before copying them, so an
+//! oversized message costs no more than reading its bytes. Limits that depend on other
+//! fields, such as the text limit of a `CastType::Cast`, are checked by [`validate_limits`].
+use crate::message::*;
+use crate::Error;
+
+/// Maximum length in bytes of `Message.data_bytes`.
+pub const MAX_DATA_BYTES_LENGTH: u32 = 2048;
+
+/// Maximum length in bytes of the text of a `CastType::Cast`.
+pub const MAX_CAST_TEXT_LENGTH: u32 = 320;
+
+/// Maximum length in bytes of the text of a `CastType::LongCast`.
+pub const MAX_LONG_CAST_TEXT_LENGTH: u32 = 1024;
+
+/// Maximum number of fids mentioned by a cast.
+pub const MAX_MENTIONS: u32 = 10;
+
+/// Maximum number of embeds in a cast, counting deprecated embeds.
+pub const MAX_EMBEDS: u32 = 2;
+
+/// Maximum length in bytes of an embed, parent or reaction target URL.
+pub const MAX_URL_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of a link type.
+pub const MAX_LINK_TYPE_LENGTH: u32 = 8;
+
+/// Maximum length in bytes of a `UserDataType::Display` value.
+pub const MAX_DISPLAY_NAME_LENGTH: u32 = 32;
+
+/// Maximum length in bytes of a `UserDataType::Username` value.
+pub const MAX_USERNAME_LENGTH: u32 = 20;
+
+/// Maximum length in bytes of any other user data value.
+pub const MAX_USER_DATA_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of `FrameActionBody.url`.
+pub const MAX_FRAME_URL_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of `FrameActionBody.input_text`.
+pub const MAX_FRAME_INPUT_TEXT_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of `FrameActionBody.state`.
+pub const MAX_FRAME_STATE_LENGTH: u32 = 4096;
+
+/// Maximum length in bytes of `FrameActionBody.transaction_id`.
+pub const MAX_FRAME_TRANSACTION_ID_LENGTH: u32 = 256;
+
+/// Maximum length in bytes of `FrameActionBody.address`.
+pub const MAX_FRAME_ADDRESS_LENGTH: u32 = 64;
+
+/// Maximum length in bytes of the text input placeholder a frame shows, `fc:frame:input:text`.
+pub const MAX_FRAME_INPUT_PLACEHOLDER_LENGTH: u32 = 32;
+
+fn check(len: usize, max: u32) -> Result<(), Error> {
+    if len > max as usize {
+        Err(Error::TooLong)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks every field of a `MessageData` body against the hub's size limits.
+///
+/// # Arguments
+///
+/// * `data` - The `MessageData` to check.
+///
+/// # Returns
+///
+/// * `Ok(())` if every field is within its limit.
+/// * `Err(Error::TooLong)` if a field or list is over its limit.
+/// * `Err(Error::InvalidMessage)` if a limit depends on an unknown enum value.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::limits::validate_limits;
+/// use pallet_farcaster_frame::message::{message_data::Body, CastAddBody, MessageData};
+/// use pallet_farcaster_frame::Error;
+///
+/// let data = MessageData {
+///     body: Some(Body::CastAddBody(CastAddBody {
+///         text: "a".repeat(321),
+///         ..Default::default()
+///     })),
+///     ..Default::default()
+/// };
+/// assert_eq!(validate_limits(&data), Err(Error::TooLong));
+/// ```
+pub fn validate_limits(data: &MessageData) -> Result<(), Error> {
+    use message_data::Body;
+    match &data.body {
+        Some(Body::CastAddBody(body)) => validate_cast_add(body),
+        Some(Body::ReactionBody(body)) => match &body.target {
+            Some(reaction_body::Target::TargetUrl(url)) => check(url.len(), MAX_URL_LENGTH),
+            _ => Ok(()),
+        },
+        Some(Body::UserDataBody(body)) => {
+            let max = match body.user_data_type()? {
+                UserDataType::Display => MAX_DISPLAY_NAME_LENGTH,
+                UserDataType::Username => MAX_USERNAME_LENGTH,
+                _ => MAX_USER_DATA_LENGTH,
+            };
+            check(body.value.len(), max)
+        }
+        Some(Body::LinkBody(body)) => check(body.r#type.len(), MAX_LINK_TYPE_LENGTH),
+        Some(Body::LinkCompactStateBody(body)) => check(body.r#type.len(), MAX_LINK_TYPE_LENGTH),
+        Some(Body::FrameActionBody(body)) => {
+            check(body.url.len(), MAX_FRAME_URL_LENGTH)?;
+            check(body.input_text.len(), MAX_FRAME_INPUT_TEXT_LENGTH)?;
+            check(body.state.len(), MAX_FRAME_STATE_LENGTH)?;
+            check(body.transaction_id.len(), MAX_FRAME_TRANSACTION_ID_LENGTH)?;
+            check(body.address.len(), MAX_FRAME_ADDRESS_LENGTH)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn validate_cast_add(body: &CastAddBody) -> Result<(), Error> {
+    let max_text = match body.cast_type()? {
+        CastType::Cast => MAX_CAST_TEXT_LENGTH,
+        CastType::LongCast => MAX_LONG_CAST_TEXT_LENGTH,
+    };
+    check(body.text.len(), max_text)?;
+    check(body.mentions.len(), MAX_MENTIONS)?;
+    check(body.mentions_positions.len(), MAX_MENTIONS)?;
+    check(body.embeds.len() + body.embeds_deprecated.len(), MAX_EMBEDS)?;
+    for url in &body.embeds_deprecated {
+        check(url.len(), MAX_URL_LENGTH)?;
+    }
+    for embed in &body.embeds {
+        if let Some(embed::Embed::Url(url)) = &embed.embed {
+            check(url.len(), MAX_URL_LENGTH)?;
+        }
+    }
+    match &body.parent {
+        Some(cast_add_body::Parent::ParentUrl(url)) => check(url.len(), MAX_URL_LENGTH),
+        _ => Ok(()),
+    }
+}
@@ -5,42 +5,60 @@ use parity_scale_codec::{Encode, Decode};
 /// *
 /// A Message is a delta operation on the Farcaster network. The message protobuf is an envelope
 /// that wraps a MessageData object and contains a hash and signature which can verify its authenticity.
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct Message {
     /// Contents of the message
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub data: Option<MessageData>,
     /// Hash digest of data
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub hash: alloc::vec::Vec<u8>,
     /// Hash scheme that produced the hash digest
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hash_scheme"))]
     pub hash_scheme: i32,
     /// Signature of the hash digest
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub signature: alloc::vec::Vec<u8>,
     /// Signature scheme that produced the signature
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::signature_scheme"))]
     pub signature_scheme: i32,
     /// Public key or address of the key pair that produced the signature
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub signer: alloc::vec::Vec<u8>,
     /// MessageData serialized to bytes if using protobuf serialization other than ts-proto
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::optional_base64_bytes", skip_serializing_if = "Option::is_none"))]
     pub data_bytes: Option<alloc::vec::Vec<u8>>,
 }
 /// *
 /// A MessageData object contains properties common to all messages and wraps a body object which
 /// contains properties specific to the MessageType.
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct MessageData {
     /// Type of message contained in the body
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::message_type"))]
     pub r#type: i32,
     /// Farcaster ID of the user producing the message
     pub fid: u64,
     /// Farcaster epoch timestamp in seconds
     pub timestamp: u32,
     /// Farcaster network the message is intended for
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::farcaster_network"))]
     pub network: i32,
-    pub body: Option<message_data::Body>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+pub body: Option<message_data::Body>,
 }
 /// Nested message and enum types in `MessageData`.
 pub mod message_data {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
         #[derive(Encode, Decode)]
     pub enum Body {
         CastAddBody(super::CastAddBody),
@@ -59,21 +77,31 @@ pub mod message_data {
     }
 }
 /// * Adds metadata about a user 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct UserDataBody {
     /// Type of metadata
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::user_data_type"))]
     pub r#type: i32,
     /// Value of the metadata
     pub value: alloc::string::String,
 }
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct Embed {
-    pub embed: Option<embed::Embed>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+pub embed: Option<embed::Embed>,
 }
 /// Nested message and enum types in `Embed`.
 pub mod embed {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
         #[derive(Encode, Decode)]
     pub enum Embed {
         Url(alloc::string::String),
@@ -81,6 +109,9 @@ pub mod embed {
     }
 }
 /// * Adds a new Cast 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct CastAddBody {
     /// URLs to be embedded in the cast
@@ -94,13 +125,17 @@ pub struct CastAddBody {
     /// URLs or cast ids to be embedded in the cast
     pub embeds: alloc::vec::Vec<Embed>,
     /// Type of cast
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::cast_type"))]
     pub r#type: i32,
-    pub parent: Option<cast_add_body::Parent>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+pub parent: Option<cast_add_body::Parent>,
 }
 /// Nested message and enum types in `CastAddBody`.
 pub mod cast_add_body {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
         #[derive(Encode, Decode)]
     pub enum Parent {
         /// Parent cast of the cast
@@ -110,30 +145,45 @@ pub mod cast_add_body {
     }
 }
 /// * Removes an existing Cast 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct CastRemoveBody {
     /// Hash of the cast to remove
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub target_hash: alloc::vec::Vec<u8>,
 }
 /// * Identifier used to look up a Cast 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct CastId {
     /// Fid of the user who created the cast
     pub fid: u64,
     /// Hash of the cast
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub hash: alloc::vec::Vec<u8>,
 }
 /// * Adds or removes a Reaction from a Cast 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct ReactionBody {
     /// Type of reaction
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::reaction_type"))]
     pub r#type: i32,
-    pub target: Option<reaction_body::Target>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+pub target: Option<reaction_body::Target>,
 }
 /// Nested message and enum types in `ReactionBody`.
 pub mod reaction_body {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
         #[derive(Encode, Decode)]
     pub enum Target {
         /// CastId of the Cast to react to
@@ -143,42 +193,60 @@ pub mod reaction_body {
     }
 }
 /// * Adds a Verification of ownership of an Address based on Protocol 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct VerificationAddAddressBody {
     /// Address being verified for a given Protocol
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub address: alloc::vec::Vec<u8>,
     /// Signature produced by the user's address for a given Protocol
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub claim_signature: alloc::vec::Vec<u8>,
     /// Hash of the latest Ethereum block when the signature was produced
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub block_hash: alloc::vec::Vec<u8>,
     /// Type of verification. 0 = EOA, 1 = contract
     pub verification_type: u32,
     /// 0 for EOA verifications, 1 or 10 for contract verifications
     pub chain_id: u32,
     /// Protocol of the Verification
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::protocol"))]
     pub protocol: i32,
 }
 /// * Removes a Verification of a given protocol 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct VerificationRemoveBody {
     /// Address of the Verification to remove
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub address: alloc::vec::Vec<u8>,
     /// Protocol of the Verification to remove
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::protocol"))]
     pub protocol: i32,
 }
 /// * Adds or removes a Link 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct LinkBody {
     /// Type of link, <= 8 characters
     pub r#type: alloc::string::String,
     /// User-defined timestamp that preserves original timestamp when message.data.timestamp needs to be updated for compaction
     pub display_timestamp: Option<u32>,
-    pub target: Option<link_body::Target>,
+        #[cfg_attr(feature = "serde", serde(flatten))]
+pub target: Option<link_body::Target>,
 }
 /// Nested message and enum types in `LinkBody`.
 pub mod link_body {
     use super::*;
 
+    #[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "camelCase"))]
         #[derive(Encode, Decode)]
     pub enum Target {
         /// The fid the link relates to
@@ -186,6 +254,9 @@ pub mod link_body {
     }
 }
 /// * A Compaction message for the Link Store 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct LinkCompactStateBody {
     /// Type of link, <= 8 characters
@@ -193,33 +264,51 @@ pub struct LinkCompactStateBody {
     pub target_fids: alloc::vec::Vec<u64>,
 }
 /// * A Farcaster Frame action 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct FrameActionBody {
     /// URL of the Frame triggering the action
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub url: alloc::vec::Vec<u8>,
     /// The index of the button pressed (1-4)
     pub button_index: u32,
     /// The cast which contained the frame url
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub cast_id: Option<CastId>,
     /// Text input from the user, if present
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub input_text: alloc::vec::Vec<u8>,
     /// Serialized frame state value
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub state: alloc::vec::Vec<u8>,
     /// Chain-specific transaction ID for tx actions
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub transaction_id: alloc::vec::Vec<u8>,
     /// Chain-specific address for tx actions
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub address: alloc::vec::Vec<u8>,
 }
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default, rename_all = "camelCase"))]
 #[derive(Encode, Decode)]
 pub struct UserNameProof {
     pub timestamp: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub name: alloc::vec::Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::hex_bytes"))]
     pub owner: alloc::vec::Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::base64_bytes"))]
     pub signature: alloc::vec::Vec<u8>,
     pub fid: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::json::user_name_type"))]
     pub r#type: i32,
 }
 /// * Type of hashing scheme used to produce a digest of MessageData 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum HashScheme {
     None = 0,
@@ -247,6 +336,8 @@ impl HashScheme {
     }
 }
 /// * Type of signature scheme used to sign the Message hash  
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum SignatureScheme {
     None = 0,
@@ -278,6 +369,8 @@ impl SignatureScheme {
     }
 }
 /// * Type of the MessageBody 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum MessageType {
     None = 0,
@@ -353,6 +446,8 @@ impl MessageType {
     }
 }
 /// * Farcaster network the message is intended for 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum FarcasterNetwork {
     None = 0,
@@ -388,6 +483,8 @@ impl FarcasterNetwork {
     }
 }
 /// * Type of UserData 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum UserDataType {
     None = 0,
@@ -431,6 +528,8 @@ impl UserDataType {
     }
 }
 /// * Type of cast 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum CastType {
     Cast = 0,
@@ -457,6 +556,8 @@ impl CastType {
     }
 }
 /// * Type of Reaction 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum ReactionType {
     None = 0,
@@ -488,6 +589,8 @@ impl ReactionType {
     }
 }
 /// * Type of Protocol to disambiguate verification addresses 
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum Protocol {
     Ethereum = 0,
@@ -513,6 +616,8 @@ impl Protocol {
         }
     }
 }
+#[derive(Clone, Debug, PartialEq, Eq, scale_info::TypeInfo)]
+#[derive(Copy)]
 #[derive(Encode, Decode)]
 pub enum UserNameType {
     UsernameTypeNone = 0,
@@ -0,0 +1,109 @@
+use crate::message::FarcasterNetwork;
+use crate::pallet as pallet_farcaster_frame;
+use crate::pallet::FrameActionHandler;
+use crate::VerifiedFrameAction;
+use core::cell::RefCell;
+use core::time::Duration;
+use frame_support::dispatch::{DispatchResultWithPostInfo, WithPostDispatchInfo};
+use frame_support::weights::Weight;
+use frame_support::{derive_impl, parameter_types, traits::UnixTime};
+use sp_runtime::{BuildStorage, DispatchError};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        FarcasterFrame: pallet_farcaster_frame,
+    }
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+    type Block = Block;
+}
+
+parameter_types! {
+    pub const MaxMessageLength: u32 = 1024;
+    pub const MaxUrlLength: u32 = 128;
+    pub const Network: FarcasterNetwork = FarcasterNetwork::Mainnet;
+    /// Frame origins accepted by the pallet, any by default.
+    pub static FrameOrigins: &'static [&'static str] = &[];
+    pub const MaxMessageAge: u32 = 3600;
+    pub const ReplayWindow: u32 = 600;
+    /// Unix time in seconds returned by `MockTime`.
+    pub static Now: u64 = 0;
+    pub const MaxSeenMessages: u32 = 3;
+}
+
+thread_local! {
+    /// `(who, fid, button_index)` of every action handled by `FixtureHandler`.
+    pub static HANDLED: RefCell<Vec<(u64, u64, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Clock driven by `Now`.
+pub struct MockTime;
+
+impl UnixTime for MockTime {
+    fn now() -> Duration {
+        Duration::from_secs(Now::get())
+    }
+}
+
+/// Claims the frame served by the test fixture.
+pub struct FixtureHandler;
+
+impl FrameActionHandler<u64> for FixtureHandler {
+    const URL_PREFIX: &'static str = "https://pelican-fond-distinctly.ngrok-free.app/";
+
+    fn weight() -> Weight {
+        Weight::from_parts(1_000_000, 0)
+    }
+
+    fn handle(who: &u64, action: &VerifiedFrameAction) -> DispatchResultWithPostInfo {
+        HANDLED.with(|handled| {
+            handled
+                .borrow_mut()
+                .push((*who, action.fid, action.button_index))
+        });
+        Ok(().into())
+    }
+}
+
+/// Claims and rejects every action from `https://reject.example/`.
+pub struct RejectHandler;
+
+impl FrameActionHandler<u64> for RejectHandler {
+    const URL_PREFIX: &'static str = "https://reject.example/";
+
+    fn weight() -> Weight {
+        Weight::from_parts(2_000_000, 0)
+    }
+
+    /// Rejects after using a quarter of its weight.
+    fn handle(_: &u64, _: &VerifiedFrameAction) -> DispatchResultWithPostInfo {
+        Err(DispatchError::Other("rejected").with_weight(Weight::from_parts(500_000, 0)))
+    }
+}
+
+impl pallet_farcaster_frame::Config for Test {
+    type MaxMessageLength = MaxMessageLength;
+    type MaxUrlLength = MaxUrlLength;
+    type Network = Network;
+    type FrameOrigins = FrameOrigins;
+    type UnixTime = MockTime;
+    type MaxMessageAge = MaxMessageAge;
+    type ReplayWindow = ReplayWindow;
+    type MaxSeenMessages = MaxSeenMessages;
+    type FrameActionHandler = (FixtureHandler, RejectHandler);
+    type WeightInfo = ();
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+    let mut ext = sp_io::TestExternalities::new(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
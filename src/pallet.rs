@@ -0,0 +1,458 @@
+//! A FRAME pallet that accepts verified Farcaster frame actions as extrinsics.
+//!
+//! Runtimes add it with `pallet_farcaster_frame::pallet` as the pallet path, since the crate
+//! root already exports the parser's own [`Error`](crate::Error).
+pub use pallet::*;
+
+use crate::frame_action::url_has_prefix;
+use crate::VerifiedFrameAction;
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use frame_support::weights::Weight;
+
+/// Runtime logic that acts on verified frame actions.
+///
+/// Each handler claims the frames it serves by URL, by default the URLs under
+/// [`URL_PREFIX`](Self::URL_PREFIX) as matched by [`url_has_prefix`]. Handlers are combined as
+/// tuples; the first handler that claims an action handles it.
+///
+/// `submit_frame_action` is weighed with [`weight`](Self::weight) on top of its own weight,
+/// and refunds whatever the handler that ran did not use.
+///
+/// # Example
+///
+/// ```ignore
+/// struct Mint;
+///
+/// impl<AccountId> FrameActionHandler<AccountId> for Mint {
+///     const URL_PREFIX: &'static str = "https://mint.example.com/";
+///
+///     fn weight() -> Weight {
+///         Weight::from_parts(50_000_000, 0)
+///     }
+///
+///     fn handle(who: &AccountId, action: &VerifiedFrameAction) -> DispatchResultWithPostInfo {
+///         // Mint to `action.fid` when `action.button_index` is 1, ...
+///         Ok(().into())
+///     }
+/// }
+/// ```
+pub trait FrameActionHandler<AccountId> {
+    /// URL prefix of the frames this handler serves, such as `https://mint.example.com/nft`.
+    const URL_PREFIX: &'static str;
+
+    /// Returns whether this handler takes actions from the frame at `url`.
+    fn claims(url: &str) -> bool {
+        url_has_prefix(url, Self::URL_PREFIX)
+    }
+
+    /// Upper bound of the weight [`handle`](Self::handle) consumes.
+    fn weight() -> Weight;
+
+    /// Handles a verified frame action.
+    ///
+    /// # Arguments
+    ///
+    /// * `who` - The account that submitted the action.
+    /// * `action` - The verified action, carrying the fid, cast id, button index, input text,
+    ///   state and transaction fields.
+    ///
+    /// # Returns
+    ///
+    /// * The weight actually consumed in `actual_weight`, or `None` for the full
+    ///   [`weight`](Self::weight), on success and on failure alike.
+    fn handle(who: &AccountId, action: &VerifiedFrameAction) -> DispatchResultWithPostInfo;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(10)]
+impl<AccountId> FrameActionHandler<AccountId> for Tuple {
+    // Unused, as `claims` asks each handler in turn.
+    const URL_PREFIX: &'static str = "";
+
+    fn claims(url: &str) -> bool {
+        for_tuples!( #( if Tuple::claims(url) { return true; } )* );
+        false
+    }
+
+    fn weight() -> Weight {
+        let mut weight = Weight::zero();
+        for_tuples!( #( weight = weight.saturating_add(Tuple::weight()); )* );
+        weight
+    }
+
+    /// Reports the weight of the handler that ran, or zero if none claimed the action.
+    fn handle(who: &AccountId, action: &VerifiedFrameAction) -> DispatchResultWithPostInfo {
+        for_tuples!( #(
+            if Tuple::claims(&action.url) {
+                return with_actual_weight(Tuple::handle(who, action), Tuple::weight());
+            }
+        )* );
+        Ok(Some(Weight::zero()).into())
+    }
+}
+
+/// Fills in `max` as the actual weight of `result` if the handler left it out.
+fn with_actual_weight(
+    result: DispatchResultWithPostInfo,
+    max: Weight,
+) -> DispatchResultWithPostInfo {
+    match result {
+        Ok(mut post_info) => {
+            post_info.actual_weight.get_or_insert(max);
+            Ok(post_info)
+        }
+        Err(mut error) => {
+            error.post_info.actual_weight.get_or_insert(max);
+            Err(error)
+        }
+    }
+}
+
+// The FRAME macros expect the pallet to live in a module named `pallet`.
+#[allow(clippy::module_inception)]
+#[frame_support::pallet]
+pub mod pallet {
+    use crate::message::*;
+    use crate::verification::HASH_LENGTH;
+    use crate::{parse_frame_action, FarcasterTime, ValidationConfig, VerifiedFrameAction};
+    use frame_support::dispatch::WithPostDispatchInfo;
+    use frame_support::pallet_prelude::*;
+    use frame_support::traits::UnixTime;
+    use frame_support::weights::constants::RocksDbWeight;
+    use frame_system::pallet_prelude::*;
+
+    /// Weight functions needed by the pallet.
+    pub trait WeightInfo {
+        fn submit_frame_action(len: u32) -> Weight;
+        fn prune_seen_messages() -> Weight;
+    }
+
+    /// Conservative weights for runtimes that have not benchmarked the pallet.
+    impl WeightInfo for () {
+        fn submit_frame_action(len: u32) -> Weight {
+            // Decoding, hashing and signature verification, plus a per-byte cost.
+            Weight::from_parts(100_000_000, 0)
+                .saturating_add(Weight::from_parts(u64::from(len).saturating_mul(20_000), 0))
+                // `SeenMessages` of the fid.
+                .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+        }
+
+        fn prune_seen_messages() -> Weight {
+            Weight::from_parts(10_000_000, 0)
+                // `SeenMessages` of the fid.
+                .saturating_add(RocksDbWeight::get().reads_writes(1, 1))
+        }
+    }
+
+    /// A processed message remembered for replay protection.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+    pub struct SeenMessage {
+        /// `Message.hash` of the processed message.
+        pub hash: [u8; HASH_LENGTH],
+        /// `MessageData.timestamp` of the processed message.
+        pub timestamp: u32,
+    }
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    pub use super::FrameActionHandler;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config<RuntimeEvent: From<Event<Self>>> {
+        /// Maximum length of the protobuf-encoded message accepted by `submit_frame_action`.
+        #[pallet::constant]
+        type MaxMessageLength: Get<u32>;
+
+        /// Maximum length of a frame URL carried in events.
+        #[pallet::constant]
+        type MaxUrlLength: Get<u32>;
+
+        /// The Farcaster network submitted messages must be intended for.
+        type Network: Get<FarcasterNetwork>;
+
+        /// Origins of the frames actions may come from, such as `https://frames.example.com`,
+        /// or an empty list to accept any origin.
+        type FrameOrigins: Get<&'static [&'static str]>;
+
+        /// Source of the current time, usually `pallet_timestamp`.
+        type UnixTime: UnixTime;
+
+        /// How many seconds old a message may be when it is submitted.
+        #[pallet::constant]
+        type MaxMessageAge: Get<u32>;
+
+        /// How many seconds processed messages are remembered, by `UnixTime`. Messages whose
+        /// `MessageData.timestamp` is older are forgotten and rejected.
+        #[pallet::constant]
+        type ReplayWindow: Get<u32>;
+
+        /// Maximum number of messages remembered per fid within `ReplayWindow`.
+        #[pallet::constant]
+        type MaxSeenMessages: Get<u32>;
+
+        /// Handlers for verified frame actions. Actions no handler claims are only recorded
+        /// in `FrameActionSubmitted`.
+        type FrameActionHandler: FrameActionHandler<Self::AccountId>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    /// Hashes of the messages processed per fid within the replay window.
+    #[pallet::storage]
+    pub type SeenMessages<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        u64,
+        BoundedVec<SeenMessage, T::MaxSeenMessages>,
+        ValueQuery,
+    >;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A verified frame action was submitted.
+        FrameActionSubmitted {
+            who: T::AccountId,
+            fid: u64,
+            url: BoundedVec<u8, T::MaxUrlLength>,
+            button_index: u32,
+        },
+        /// Messages of `fid` older than the replay window were forgotten.
+        SeenMessagesPruned { fid: u64, count: u32 },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The bytes are not a valid protobuf message.
+        InvalidProtobuf,
+        /// The message is malformed.
+        InvalidMessage,
+        /// The message carries neither `data` nor `data_bytes`.
+        MissingData,
+        /// `MessageData.fid` is zero.
+        InvalidFid,
+        /// `hash` is not a 20 byte digest.
+        InvalidHash,
+        /// `hash_scheme` is not supported.
+        UnsupportedHashScheme,
+        /// `hash` is not the digest of the message data.
+        HashMismatch,
+        /// `signature_scheme` is not supported.
+        UnsupportedSignatureScheme,
+        /// `signer` is not a valid public key for the signature scheme.
+        InvalidSigner,
+        /// `signature` was not produced by `signer` over `hash`.
+        InvalidSignature,
+        /// The verification or proof cannot be checked.
+        UnsupportedVerification,
+        /// The message is intended for another Farcaster network than `Network`.
+        WrongNetwork,
+        /// The message timestamp is too far in the future.
+        TimestampInFuture,
+        /// The message has no body.
+        MissingBody,
+        /// The message is not a frame action.
+        UnexpectedMessageType,
+        /// The frame URL is longer than `MaxUrlLength`.
+        UrlTooLong,
+        /// The message has already been processed.
+        DuplicateMessage,
+        /// The message is older than the replay window allows.
+        MessageTooOld,
+        /// The fid has sent `MaxSeenMessages` messages within the replay window.
+        TooManyMessages,
+        /// The message timestamp is older than `MaxMessageAge`.
+        TimestampTooOld,
+        /// The message timestamp or the current time is outside Farcaster time.
+        InvalidTimestamp,
+        /// The message names no known Farcaster network.
+        InvalidNetwork,
+        /// The message body does not match its type.
+        BodyMismatch,
+        /// A message field is longer than its bound.
+        TooLong,
+        /// The frame URL is not a valid http or https URL.
+        InvalidUrl,
+        /// The button index is not between 1 and 4.
+        InvalidButtonIndex,
+        /// The frame URL is not from one of `FrameOrigins`.
+        UrlNotAllowed,
+        /// The address is not a 20 byte EVM address.
+        InvalidAddress,
+        /// The transaction id is not a 32 byte transaction hash.
+        InvalidTransactionId,
+        /// The chain id is not a supported CAIP-2 chain id.
+        InvalidChainId,
+        /// The fid has no messages older than the replay window.
+        NothingToPrune,
+    }
+
+    impl<T> From<crate::Error> for Error<T> {
+        fn from(error: crate::Error) -> Self {
+            match error {
+                crate::Error::InvalidProtobuf => Error::InvalidProtobuf,
+                crate::Error::InvalidMessage => Error::InvalidMessage,
+                crate::Error::MissingData => Error::MissingData,
+                crate::Error::InvalidFid => Error::InvalidFid,
+                crate::Error::InvalidHash => Error::InvalidHash,
+                crate::Error::UnsupportedHashScheme => Error::UnsupportedHashScheme,
+                crate::Error::HashMismatch => Error::HashMismatch,
+                crate::Error::UnsupportedSignatureScheme => Error::UnsupportedSignatureScheme,
+                crate::Error::InvalidSigner => Error::InvalidSigner,
+                crate::Error::InvalidSignature => Error::InvalidSignature,
+                crate::Error::UnsupportedVerification => Error::UnsupportedVerification,
+                crate::Error::WrongNetwork => Error::WrongNetwork,
+                crate::Error::TimestampInFuture => Error::TimestampInFuture,
+                crate::Error::TimestampTooOld => Error::TimestampTooOld,
+                crate::Error::InvalidTimestamp => Error::InvalidTimestamp,
+                crate::Error::InvalidNetwork => Error::InvalidNetwork,
+                crate::Error::BodyMismatch => Error::BodyMismatch,
+                crate::Error::TooLong => Error::TooLong,
+                crate::Error::MissingBody => Error::MissingBody,
+                crate::Error::UnexpectedMessageType => Error::UnexpectedMessageType,
+                crate::Error::InvalidUrl => Error::InvalidUrl,
+                crate::Error::InvalidButtonIndex => Error::InvalidButtonIndex,
+                crate::Error::UrlNotAllowed => Error::UrlNotAllowed,
+                crate::Error::InvalidAddress => Error::InvalidAddress,
+                crate::Error::InvalidTransactionId => Error::InvalidTransactionId,
+                crate::Error::InvalidChainId => Error::InvalidChainId,
+            }
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Submits a protobuf-encoded frame action message.
+        ///
+        /// The message is verified with [`parse_frame_action`](crate::parse_frame_action),
+        /// checking its hash, signature, `Network`, `FrameOrigins` and freshness against
+        /// `UnixTime`, then
+        /// passed to the `FrameActionHandler` that claims its URL before
+        /// `FrameActionSubmitted` is emitted.
+        ///
+        /// The call is weighed for the most expensive handler; the weight of handlers that do
+        /// not run, or use less, is refunded.
+        #[pallet::call_index(0)]
+        #[pallet::weight(
+            T::WeightInfo::submit_frame_action(raw.len() as u32)
+                .saturating_add(T::FrameActionHandler::weight())
+        )]
+        pub fn submit_frame_action(
+            origin: OriginFor<T>,
+            raw: BoundedVec<u8, T::MaxMessageLength>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let weight = T::WeightInfo::submit_frame_action(raw.len() as u32);
+            let (action, url) =
+                Self::accept_frame_action(&raw).map_err(|e| e.with_weight(weight))?;
+
+            let handler_weight = if T::FrameActionHandler::claims(&action.url) {
+                let handled = super::with_actual_weight(
+                    T::FrameActionHandler::handle(&who, &action),
+                    T::FrameActionHandler::weight(),
+                );
+                match handled {
+                    Ok(post_info) => post_info.actual_weight.unwrap_or_default(),
+                    Err(mut error) => {
+                        let handler_weight = error.post_info.actual_weight.unwrap_or_default();
+                        error.post_info.actual_weight = Some(weight.saturating_add(handler_weight));
+                        return Err(error);
+                    }
+                }
+            } else {
+                Weight::zero()
+            };
+
+            Self::deposit_event(Event::FrameActionSubmitted {
+                who,
+                fid: action.fid,
+                url,
+                button_index: action.button_index,
+            });
+            Ok(Some(weight.saturating_add(handler_weight)).into())
+        }
+
+        /// Forgets the messages of `fid` older than `ReplayWindow`.
+        ///
+        /// `submit_frame_action` prunes the messages of the fid it records, so this reclaims
+        /// the storage of fids that stopped submitting. Anyone may call it, free of charge when
+        /// it prunes anything.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::prune_seen_messages())]
+        pub fn prune_seen_messages(origin: OriginFor<T>, fid: u64) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            let cutoff = Self::replay_cutoff(Self::now()?);
+            let count = SeenMessages::<T>::mutate_exists(fid, |entry| {
+                let Some(seen) = entry else { return 0 };
+                let before = seen.len();
+                seen.retain(|m| m.timestamp >= cutoff);
+                let count = before - seen.len();
+                if seen.is_empty() {
+                    *entry = None;
+                }
+                count as u32
+            });
+            ensure!(count > 0, Error::<T>::NothingToPrune);
+
+            Self::deposit_event(Event::SeenMessagesPruned { fid, count });
+            Ok(Pays::No.into())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Verifies a submitted frame action and records it for replay protection.
+        fn accept_frame_action(
+            raw: &[u8],
+        ) -> Result<(VerifiedFrameAction, BoundedVec<u8, T::MaxUrlLength>), DispatchError> {
+            let now = Self::now()?;
+            let config = ValidationConfig {
+                network: Some(T::Network::get()),
+                now: Some(now),
+                max_age: Some(T::MaxMessageAge::get()),
+                frame_origins: T::FrameOrigins::get(),
+                ..Default::default()
+            };
+            let action = parse_frame_action(raw, &config).map_err(Error::<T>::from)?;
+            let url = BoundedVec::try_from(action.url.as_bytes().to_vec())
+                .map_err(|_| Error::<T>::UrlTooLong)?;
+
+            Self::note_message(action.fid, action.hash, action.timestamp.into(), now)?;
+            Ok((action, url))
+        }
+
+        /// The current time, in Farcaster time.
+        fn now() -> Result<FarcasterTime, DispatchError> {
+            FarcasterTime::from_unix_seconds(T::UnixTime::now().as_secs())
+                .map_err(|error| Error::<T>::from(error).into())
+        }
+
+        /// The oldest `MessageData.timestamp` still within the replay window at `now`.
+        fn replay_cutoff(now: FarcasterTime) -> u32 {
+            now.0.saturating_sub(T::ReplayWindow::get())
+        }
+
+        /// Records a processed message of `fid`, rejecting replays.
+        ///
+        /// Messages of the fid older than `ReplayWindow` at `now` are pruned, and such messages
+        /// are rejected since they can no longer be told apart from replays.
+        fn note_message(
+            fid: u64,
+            hash: [u8; HASH_LENGTH],
+            timestamp: u32,
+            now: FarcasterTime,
+        ) -> DispatchResult {
+            let cutoff = Self::replay_cutoff(now);
+            ensure!(timestamp >= cutoff, Error::<T>::MessageTooOld);
+            SeenMessages::<T>::try_mutate(fid, |seen| {
+                ensure!(
+                    !seen.iter().any(|m| m.hash == hash),
+                    Error::<T>::DuplicateMessage
+                );
+                seen.retain(|m| m.timestamp >= cutoff);
+                seen.try_push(SeenMessage { hash, timestamp })
+                    .map_err(|_| Error::<T>::TooManyMessages.into())
+            })
+        }
+    }
+}
@@ -0,0 +1,844 @@
+//! Protobuf wire-format support for the types generated in [`crate::message`].
+//!
+//! Hubs and clients exchange Farcaster messages as protobuf, so this module reads and writes
+//! the wire format defined by `message.proto` directly instead of going through SCALE.
+//!
+//! Encoding follows the hub's ts-proto output rather than plain proto3 rules, because
+//! `Message.hash` covers the exact bytes of `MessageData`: fields are written in declaration
+//! order, default scalars are skipped, and packed repeated fields are always written, even
+//! when empty.
+extern crate alloc;
+use crate::limits::*;
+use crate::message::*;
+use crate::Error;
+use alloc::{string::String, vec::Vec};
+
+/// Errors raised while reading protobuf bytes.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended in the middle of a field.
+    UnexpectedEof,
+    /// A varint was longer than 10 bytes or overflowed 64 bits.
+    VarintOverflow,
+    /// A field key had a zero or out of range field number.
+    InvalidKey,
+    /// A field used a wire type that is unknown or does not match its declared type.
+    InvalidWireType,
+    /// A `string` field did not contain valid UTF-8.
+    InvalidUtf8,
+    /// A field or repeated field was longer than its [`limits`](crate::limits).
+    LimitExceeded,
+    /// An embedded message appeared twice where a borrowed view cannot merge it.
+    DuplicateField,
+}
+
+impl From<DecodeError> for Error {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::LimitExceeded => Error::TooLong,
+            _ => Error::InvalidProtobuf,
+        }
+    }
+}
+
+/// Protobuf wire types, as carried in the low three bits of a field key.
+///
+/// The deprecated group wire types are not accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireType {
+    Varint = 0,
+    Fixed64 = 1,
+    LengthDelimited = 2,
+    Fixed32 = 5,
+}
+
+impl WireType {
+    fn from_key(key: u64) -> Result<Self, DecodeError> {
+        match key & 0x07 {
+            0 => Ok(WireType::Varint),
+            1 => Ok(WireType::Fixed64),
+            2 => Ok(WireType::LengthDelimited),
+            5 => Ok(WireType::Fixed32),
+            _ => Err(DecodeError::InvalidWireType),
+        }
+    }
+}
+
+/// A cursor over protobuf encoded bytes.
+pub struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a reader over `buf`.
+    pub fn new(buf: &'a [u8]) -> Self {
+        Reader { buf }
+    }
+
+    /// Returns `true` once every byte has been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Reads a base 128 varint.
+    pub fn read_varint(&mut self) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+        for (i, byte) in self.buf.iter().enumerate().take(10) {
+            // The tenth byte may only contribute the most significant bit.
+            if i == 9 && *byte > 0x01 {
+                return Err(DecodeError::VarintOverflow);
+            }
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.buf = &self.buf[i + 1..];
+                return Ok(value);
+            }
+        }
+        if self.buf.len() >= 10 {
+            Err(DecodeError::VarintOverflow)
+        } else {
+            Err(DecodeError::UnexpectedEof)
+        }
+    }
+
+    /// Reads a field key and splits it into its field number and wire type.
+    pub fn read_key(&mut self) -> Result<(u32, WireType), DecodeError> {
+        let key = self.read_varint()?;
+        if key > u64::from(u32::MAX) {
+            return Err(DecodeError::InvalidKey);
+        }
+        let field = (key >> 3) as u32;
+        if field == 0 {
+            return Err(DecodeError::InvalidKey);
+        }
+        Ok((field, WireType::from_key(key)?))
+    }
+
+    fn advance(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if len > self.buf.len() {
+            return Err(DecodeError::UnexpectedEof);
+        }
+        let (head, tail) = self.buf.split_at(len);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn read_length_delimited(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.read_varint()?;
+        let len = usize::try_from(len).map_err(|_| DecodeError::UnexpectedEof)?;
+        self.advance(len)
+    }
+
+    /// Skips over the value of a field this decoder does not know about.
+    pub fn skip_field(&mut self, wire_type: WireType) -> Result<(), DecodeError> {
+        match wire_type {
+            WireType::Varint => self.read_varint().map(|_| ()),
+            WireType::Fixed64 => self.advance(8).map(|_| ()),
+            WireType::LengthDelimited => self.read_length_delimited().map(|_| ()),
+            WireType::Fixed32 => self.advance(4).map(|_| ()),
+        }
+    }
+
+    /// Reads a `uint64` field.
+    pub fn read_uint64(&mut self, wire_type: WireType) -> Result<u64, DecodeError> {
+        expect(wire_type, WireType::Varint)?;
+        self.read_varint()
+    }
+
+    /// Reads a `uint32` field, truncating wider values as protobuf does.
+    pub fn read_uint32(&mut self, wire_type: WireType) -> Result<u32, DecodeError> {
+        self.read_uint64(wire_type).map(|value| value as u32)
+    }
+
+    /// Reads an enum field as its raw `i32` value.
+    pub fn read_enum(&mut self, wire_type: WireType) -> Result<i32, DecodeError> {
+        self.read_uint64(wire_type).map(|value| value as i32)
+    }
+
+    /// Reads a `bytes` field, borrowing from the input.
+    pub fn read_bytes(&mut self, wire_type: WireType) -> Result<&'a [u8], DecodeError> {
+        expect(wire_type, WireType::LengthDelimited)?;
+        self.read_length_delimited()
+    }
+
+    /// Reads a `bytes` field of at most `max` bytes, borrowing from the input.
+    pub fn read_bytes_max(
+        &mut self,
+        wire_type: WireType,
+        max: u32,
+    ) -> Result<&'a [u8], DecodeError> {
+        let bytes = self.read_bytes(wire_type)?;
+        if bytes.len() > max as usize {
+            return Err(DecodeError::LimitExceeded);
+        }
+        Ok(bytes)
+    }
+
+    /// Reads a `string` field, borrowing from the input.
+    pub fn read_str(&mut self, wire_type: WireType) -> Result<&'a str, DecodeError> {
+        let bytes = self.read_bytes(wire_type)?;
+        core::str::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    /// Reads a `string` field into an owned `String`.
+    pub fn read_string(&mut self, wire_type: WireType) -> Result<String, DecodeError> {
+        self.read_str(wire_type).map(String::from)
+    }
+
+    /// Reads a `string` field of at most `max` bytes into an owned `String`.
+    pub fn read_string_max(
+        &mut self,
+        wire_type: WireType,
+        max: u32,
+    ) -> Result<String, DecodeError> {
+        let bytes = self.read_bytes_max(wire_type, max)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| DecodeError::InvalidUtf8)
+    }
+
+    /// Reads a repeated varint field, accepting both packed and unpacked encodings.
+    ///
+    /// `push` may reject a value, e.g. once a list is over its limit.
+    pub fn read_repeated_varint(
+        &mut self,
+        wire_type: WireType,
+        mut push: impl FnMut(u64) -> Result<(), DecodeError>,
+    ) -> Result<(), DecodeError> {
+        match wire_type {
+            WireType::Varint => push(self.read_varint()?),
+            WireType::LengthDelimited => {
+                let mut packed = Reader::new(self.read_length_delimited()?);
+                while !packed.is_empty() {
+                    push(packed.read_varint()?)?;
+                }
+                Ok(())
+            }
+            _ => Err(DecodeError::InvalidWireType),
+        }
+    }
+
+    /// Reads an embedded message field and merges it into `msg`.
+    pub fn merge_message<M: ProtoMessage>(
+        &mut self,
+        wire_type: WireType,
+        msg: &mut M,
+    ) -> Result<(), DecodeError> {
+        let bytes = self.read_bytes(wire_type)?;
+        msg.merge_proto(bytes)
+    }
+
+    /// Reads an embedded message field into a new message.
+    pub fn read_message<M: ProtoMessage>(&mut self, wire_type: WireType) -> Result<M, DecodeError> {
+        let mut msg = M::default();
+        self.merge_message(wire_type, &mut msg)?;
+        Ok(msg)
+    }
+}
+
+/// A buffer that protobuf fields are written into.
+#[derive(Default)]
+pub struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    /// Creates an empty writer.
+    pub fn new() -> Self {
+        Writer::default()
+    }
+
+    /// Returns the bytes written so far.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.buf
+    }
+
+    /// Writes a base 128 varint.
+    pub fn write_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    /// Writes a field key.
+    pub fn write_key(&mut self, field: u32, wire_type: WireType) {
+        self.write_varint((u64::from(field) << 3) | wire_type as u64);
+    }
+
+    /// Writes a varint field, even if it holds the default value.
+    pub fn write_varint_field(&mut self, field: u32, value: u64) {
+        self.write_key(field, WireType::Varint);
+        self.write_varint(value);
+    }
+
+    /// Writes a length-delimited field, even if it is empty.
+    pub fn write_length_delimited(&mut self, field: u32, bytes: &[u8]) {
+        self.write_key(field, WireType::LengthDelimited);
+        self.write_varint(bytes.len() as u64);
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Writes a `uint64` field unless it is zero.
+    pub fn write_uint64(&mut self, field: u32, value: u64) {
+        if value != 0 {
+            self.write_varint_field(field, value);
+        }
+    }
+
+    /// Writes a `uint32` field unless it is zero.
+    pub fn write_uint32(&mut self, field: u32, value: u32) {
+        self.write_uint64(field, u64::from(value));
+    }
+
+    /// Writes an enum field unless it is zero. Negative values are sign-extended.
+    pub fn write_enum(&mut self, field: u32, value: i32) {
+        self.write_uint64(field, i64::from(value) as u64);
+    }
+
+    /// Writes a `bytes` field unless it is empty.
+    pub fn write_bytes(&mut self, field: u32, bytes: &[u8]) {
+        if !bytes.is_empty() {
+            self.write_length_delimited(field, bytes);
+        }
+    }
+
+    /// Writes a `string` field unless it is empty.
+    pub fn write_string(&mut self, field: u32, value: &str) {
+        self.write_bytes(field, value.as_bytes());
+    }
+
+    /// Writes a packed repeated varint field. Like the hub, this writes the field even when
+    /// there are no values.
+    pub fn write_packed(&mut self, field: u32, values: impl Iterator<Item = u64>) {
+        let mut packed = Writer::new();
+        for value in values {
+            packed.write_varint(value);
+        }
+        self.write_length_delimited(field, &packed.buf);
+    }
+
+    /// Writes an embedded message field.
+    pub fn write_message<M: ProtoMessage>(&mut self, field: u32, msg: &M) {
+        self.write_length_delimited(field, &msg.encode_proto());
+    }
+}
+
+/// Appends `value` to a repeated field that may hold at most `max` values.
+fn push_max<T>(values: &mut Vec<T>, value: T, max: u32) -> Result<(), DecodeError> {
+    if values.len() >= max as usize {
+        return Err(DecodeError::LimitExceeded);
+    }
+    values.push(value);
+    Ok(())
+}
+
+fn expect(actual: WireType, expected: WireType) -> Result<(), DecodeError> {
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(DecodeError::InvalidWireType)
+    }
+}
+
+/// A message that can be read from and written to the protobuf wire format.
+pub trait ProtoMessage: Default {
+    /// Merges a single field into `self`. Unknown fields are skipped.
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError>;
+
+    /// Merges every field in `bytes` into `self`, following protobuf merge semantics.
+    fn merge_proto(&mut self, bytes: &[u8]) -> Result<(), DecodeError> {
+        let mut reader = Reader::new(bytes);
+        while !reader.is_empty() {
+            let (field, wire_type) = reader.read_key()?;
+            self.merge_field(field, wire_type, &mut reader)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes a message from protobuf bytes.
+    fn decode_proto(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut msg = Self::default();
+        msg.merge_proto(bytes)?;
+        Ok(msg)
+    }
+
+    /// Writes every field of `self` to `writer`.
+    fn encode_fields(&self, writer: &mut Writer);
+
+    /// Encodes the message to protobuf bytes.
+    fn encode_proto(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        self.encode_fields(&mut writer);
+        writer.into_inner()
+    }
+}
+
+/// Merges an embedded message into a oneof, replacing any other variant that was set.
+macro_rules! merge_oneof {
+    ($target:expr, $variant:path, $wire_type:expr, $reader:expr) => {
+        match &mut $target {
+            Some($variant(msg)) => $reader.merge_message($wire_type, msg)?,
+            _ => $target = Some($variant($reader.read_message($wire_type)?)),
+        }
+    };
+}
+
+impl ProtoMessage for Message {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => reader.merge_message(wire_type, self.data.get_or_insert_with(Default::default))?,
+            2 => self.hash = reader.read_bytes(wire_type)?.to_vec(),
+            3 => self.hash_scheme = reader.read_enum(wire_type)?,
+            4 => self.signature = reader.read_bytes(wire_type)?.to_vec(),
+            5 => self.signature_scheme = reader.read_enum(wire_type)?,
+            6 => self.signer = reader.read_bytes(wire_type)?.to_vec(),
+            7 => {
+                let data_bytes = reader.read_bytes_max(wire_type, MAX_DATA_BYTES_LENGTH)?;
+                self.data_bytes = Some(data_bytes.to_vec());
+            }
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        if let Some(data) = &self.data {
+            writer.write_message(1, data);
+        }
+        writer.write_bytes(2, &self.hash);
+        writer.write_enum(3, self.hash_scheme);
+        writer.write_bytes(4, &self.signature);
+        writer.write_enum(5, self.signature_scheme);
+        writer.write_bytes(6, &self.signer);
+        if let Some(data_bytes) = &self.data_bytes {
+            writer.write_length_delimited(7, data_bytes);
+        }
+    }
+}
+
+impl ProtoMessage for MessageData {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        use message_data::Body;
+        match field {
+            1 => self.r#type = reader.read_enum(wire_type)?,
+            2 => self.fid = reader.read_uint64(wire_type)?,
+            3 => self.timestamp = reader.read_uint32(wire_type)?,
+            4 => self.network = reader.read_enum(wire_type)?,
+            5 => merge_oneof!(self.body, Body::CastAddBody, wire_type, reader),
+            6 => merge_oneof!(self.body, Body::CastRemoveBody, wire_type, reader),
+            7 => merge_oneof!(self.body, Body::ReactionBody, wire_type, reader),
+            9 => merge_oneof!(
+                self.body,
+                Body::VerificationAddAddressBody,
+                wire_type,
+                reader
+            ),
+            10 => merge_oneof!(self.body, Body::VerificationRemoveBody, wire_type, reader),
+            12 => merge_oneof!(self.body, Body::UserDataBody, wire_type, reader),
+            14 => merge_oneof!(self.body, Body::LinkBody, wire_type, reader),
+            15 => merge_oneof!(self.body, Body::UsernameProofBody, wire_type, reader),
+            16 => merge_oneof!(self.body, Body::FrameActionBody, wire_type, reader),
+            17 => merge_oneof!(self.body, Body::LinkCompactStateBody, wire_type, reader),
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        use message_data::Body;
+        writer.write_enum(1, self.r#type);
+        writer.write_uint64(2, self.fid);
+        writer.write_uint32(3, self.timestamp);
+        writer.write_enum(4, self.network);
+        match &self.body {
+            Some(Body::CastAddBody(body)) => writer.write_message(5, body),
+            Some(Body::CastRemoveBody(body)) => writer.write_message(6, body),
+            Some(Body::ReactionBody(body)) => writer.write_message(7, body),
+            Some(Body::VerificationAddAddressBody(body)) => writer.write_message(9, body),
+            Some(Body::VerificationRemoveBody(body)) => writer.write_message(10, body),
+            Some(Body::UserDataBody(body)) => writer.write_message(12, body),
+            Some(Body::LinkBody(body)) => writer.write_message(14, body),
+            Some(Body::UsernameProofBody(body)) => writer.write_message(15, body),
+            Some(Body::FrameActionBody(body)) => writer.write_message(16, body),
+            Some(Body::LinkCompactStateBody(body)) => writer.write_message(17, body),
+            None => {}
+        }
+    }
+}
+
+impl ProtoMessage for UserDataBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.r#type = reader.read_enum(wire_type)?,
+            2 => self.value = reader.read_string_max(wire_type, MAX_USER_DATA_LENGTH)?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_enum(1, self.r#type);
+        writer.write_string(2, &self.value);
+    }
+}
+
+impl ProtoMessage for Embed {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => {
+                let url = reader.read_string_max(wire_type, MAX_URL_LENGTH)?;
+                self.embed = Some(embed::Embed::Url(url));
+            }
+            2 => merge_oneof!(self.embed, embed::Embed::CastId, wire_type, reader),
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        match &self.embed {
+            Some(embed::Embed::Url(url)) => writer.write_length_delimited(1, url.as_bytes()),
+            Some(embed::Embed::CastId(cast_id)) => writer.write_message(2, cast_id),
+            None => {}
+        }
+    }
+}
+
+impl ProtoMessage for CastAddBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        use cast_add_body::Parent;
+        match field {
+            1 => {
+                let url = reader.read_string_max(wire_type, MAX_URL_LENGTH)?;
+                push_max(&mut self.embeds_deprecated, url, MAX_EMBEDS)?;
+            }
+            2 => reader.read_repeated_varint(wire_type, |fid| {
+                push_max(&mut self.mentions, fid, MAX_MENTIONS)
+            })?,
+            3 => merge_oneof!(self.parent, Parent::ParentCastId, wire_type, reader),
+            // `type` may follow the text, so only the long cast limit applies here.
+            4 => self.text = reader.read_string_max(wire_type, MAX_LONG_CAST_TEXT_LENGTH)?,
+            5 => reader.read_repeated_varint(wire_type, |position| {
+                push_max(&mut self.mentions_positions, position as u32, MAX_MENTIONS)
+            })?,
+            6 => {
+                let embed = reader.read_message(wire_type)?;
+                push_max(&mut self.embeds, embed, MAX_EMBEDS)?;
+            }
+            7 => {
+                let url = reader.read_string_max(wire_type, MAX_URL_LENGTH)?;
+                self.parent = Some(Parent::ParentUrl(url));
+            }
+            8 => self.r#type = reader.read_enum(wire_type)?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        use cast_add_body::Parent;
+        for url in &self.embeds_deprecated {
+            writer.write_length_delimited(1, url.as_bytes());
+        }
+        writer.write_packed(2, self.mentions.iter().copied());
+        // The hub writes the `parent` oneof where it is declared, ahead of `text`.
+        match &self.parent {
+            Some(Parent::ParentCastId(cast_id)) => writer.write_message(3, cast_id),
+            Some(Parent::ParentUrl(url)) => writer.write_length_delimited(7, url.as_bytes()),
+            None => {}
+        }
+        writer.write_string(4, &self.text);
+        writer.write_packed(5, self.mentions_positions.iter().map(|&p| u64::from(p)));
+        for embed in &self.embeds {
+            writer.write_message(6, embed);
+        }
+        writer.write_enum(8, self.r#type);
+    }
+}
+
+impl ProtoMessage for CastRemoveBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.target_hash = reader.read_bytes(wire_type)?.to_vec(),
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_bytes(1, &self.target_hash);
+    }
+}
+
+impl ProtoMessage for CastId {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.fid = reader.read_uint64(wire_type)?,
+            2 => self.hash = reader.read_bytes(wire_type)?.to_vec(),
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_uint64(1, self.fid);
+        writer.write_bytes(2, &self.hash);
+    }
+}
+
+impl ProtoMessage for ReactionBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        use reaction_body::Target;
+        match field {
+            1 => self.r#type = reader.read_enum(wire_type)?,
+            2 => merge_oneof!(self.target, Target::TargetCastId, wire_type, reader),
+            3 => {
+                let url = reader.read_string_max(wire_type, MAX_URL_LENGTH)?;
+                self.target = Some(Target::TargetUrl(url));
+            }
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        use reaction_body::Target;
+        writer.write_enum(1, self.r#type);
+        match &self.target {
+            Some(Target::TargetCastId(cast_id)) => writer.write_message(2, cast_id),
+            Some(Target::TargetUrl(url)) => writer.write_length_delimited(3, url.as_bytes()),
+            None => {}
+        }
+    }
+}
+
+impl ProtoMessage for VerificationAddAddressBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.address = reader.read_bytes(wire_type)?.to_vec(),
+            2 => self.claim_signature = reader.read_bytes(wire_type)?.to_vec(),
+            3 => self.block_hash = reader.read_bytes(wire_type)?.to_vec(),
+            4 => self.verification_type = reader.read_uint32(wire_type)?,
+            5 => self.chain_id = reader.read_uint32(wire_type)?,
+            7 => self.protocol = reader.read_enum(wire_type)?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_bytes(1, &self.address);
+        writer.write_bytes(2, &self.claim_signature);
+        writer.write_bytes(3, &self.block_hash);
+        writer.write_uint32(4, self.verification_type);
+        writer.write_uint32(5, self.chain_id);
+        writer.write_enum(7, self.protocol);
+    }
+}
+
+impl ProtoMessage for VerificationRemoveBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.address = reader.read_bytes(wire_type)?.to_vec(),
+            2 => self.protocol = reader.read_enum(wire_type)?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_bytes(1, &self.address);
+        writer.write_enum(2, self.protocol);
+    }
+}
+
+impl ProtoMessage for LinkBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.r#type = reader.read_string_max(wire_type, MAX_LINK_TYPE_LENGTH)?,
+            2 => self.display_timestamp = Some(reader.read_uint32(wire_type)?),
+            3 => self.target = Some(link_body::Target::TargetFid(reader.read_uint64(wire_type)?)),
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_string(1, &self.r#type);
+        if let Some(display_timestamp) = self.display_timestamp {
+            writer.write_varint_field(2, u64::from(display_timestamp));
+        }
+        if let Some(link_body::Target::TargetFid(fid)) = self.target {
+            writer.write_varint_field(3, fid);
+        }
+    }
+}
+
+impl ProtoMessage for LinkCompactStateBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.r#type = reader.read_string_max(wire_type, MAX_LINK_TYPE_LENGTH)?,
+            2 => reader.read_repeated_varint(wire_type, |fid| {
+                self.target_fids.push(fid);
+                Ok(())
+            })?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_string(1, &self.r#type);
+        writer.write_packed(2, self.target_fids.iter().copied());
+    }
+}
+
+impl ProtoMessage for FrameActionBody {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => {
+                self.url = reader
+                    .read_bytes_max(wire_type, MAX_FRAME_URL_LENGTH)?
+                    .to_vec()
+            }
+            2 => self.button_index = reader.read_uint32(wire_type)?,
+            3 => reader
+                .merge_message(wire_type, self.cast_id.get_or_insert_with(Default::default))?,
+            4 => {
+                self.input_text = reader
+                    .read_bytes_max(wire_type, MAX_FRAME_INPUT_TEXT_LENGTH)?
+                    .to_vec()
+            }
+            5 => {
+                self.state = reader
+                    .read_bytes_max(wire_type, MAX_FRAME_STATE_LENGTH)?
+                    .to_vec()
+            }
+            6 => {
+                self.transaction_id = reader
+                    .read_bytes_max(wire_type, MAX_FRAME_TRANSACTION_ID_LENGTH)?
+                    .to_vec()
+            }
+            7 => {
+                self.address = reader
+                    .read_bytes_max(wire_type, MAX_FRAME_ADDRESS_LENGTH)?
+                    .to_vec()
+            }
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_bytes(1, &self.url);
+        writer.write_uint32(2, self.button_index);
+        if let Some(cast_id) = &self.cast_id {
+            writer.write_message(3, cast_id);
+        }
+        writer.write_bytes(4, &self.input_text);
+        writer.write_bytes(5, &self.state);
+        writer.write_bytes(6, &self.transaction_id);
+        writer.write_bytes(7, &self.address);
+    }
+}
+
+impl ProtoMessage for UserNameProof {
+    fn merge_field(
+        &mut self,
+        field: u32,
+        wire_type: WireType,
+        reader: &mut Reader<'_>,
+    ) -> Result<(), DecodeError> {
+        match field {
+            1 => self.timestamp = reader.read_uint64(wire_type)?,
+            2 => self.name = reader.read_bytes(wire_type)?.to_vec(),
+            3 => self.owner = reader.read_bytes(wire_type)?.to_vec(),
+            4 => self.signature = reader.read_bytes(wire_type)?.to_vec(),
+            5 => self.fid = reader.read_uint64(wire_type)?,
+            6 => self.r#type = reader.read_enum(wire_type)?,
+            _ => reader.skip_field(wire_type)?,
+        }
+        Ok(())
+    }
+
+    fn encode_fields(&self, writer: &mut Writer) {
+        writer.write_uint64(1, self.timestamp);
+        writer.write_bytes(2, &self.name);
+        writer.write_bytes(3, &self.owner);
+        writer.write_bytes(4, &self.signature);
+        writer.write_uint64(5, self.fid);
+        writer.write_enum(6, self.r#type);
+    }
+}
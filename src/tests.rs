@@ -2,12 +2,45 @@ use crate::message::*;
 use crate::*;
 use base64::prelude::*;
 use hex_literal::hex;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode};
+use protobuf::{DecodeError, ProtoMessage};
+use validation::MAX_FUTURE_DRIFT;
 
-// Convert an ISO8601 time to a u32 timestamp (in seconds).
-// Here we hardcode the computed value for "1973-02-28T09:13:52Z".
+// Farcaster time of "2024-03-01T09:13:52Z", in seconds since 2021-01-01T00:00:00Z.
 const TIMESTAMP: u32 = 99_825_232;
 
+// Ed25519 key used to sign messages built by the tests.
+const SIGNER_KEY: [u8; 32] = [7; 32];
+
+fn sign_message(data: MessageData) -> Message {
+    builder::sign_message_data(data, &builder::SigningKey::from_bytes(&SIGNER_KEY))
+}
+
+// Protobuf encoding of the message built by `generate_message`.
+const MESSAGE_BYTES: [u8; 225] = hex!(
+    "0a61080d109dd41118d0eccc2f20018201510a3168747470733a2f2f70656c69"
+    "63616e2d666f6e642d64697374696e63746c792e6e67726f6b2d667265652e61"
+    "70702f6f6710011a1a089dd41112140000000000000000000000000000000000"
+    "0000011214458cddef6ac58651e9a869d8bbd05eba059ae43118012240ffa7e6"
+    "aab473c551bad295c8d413f5f64b0af251d23f3b2936f4e5450395c562c4821d"
+    "4c0fd24a3fca791837964da971f959897951b58d321d0ade13a5b69d04280132"
+    "20ea4a6c63e29c520abef5507b132ec5f9954776aebebe7b92421eea691446d2"
+    "2c"
+);
+
+// A frame action as signed by a hub, with the hub's blake3 hash and the user's ed25519
+// signature. Its data is the `generate_message` frame action at 2024-02-29T09:13:52Z.
+const HUB_MESSAGE_BYTES: [u8; 225] = hex!(
+    "0a61080d109dd41118d0c9c72f20018201510a3168747470733a2f2f70656c69"
+    "63616e2d666f6e642d64697374696e63746c792e6e67726f6b2d667265652e61"
+    "70702f6f6710011a1a089dd41112140000000000000000000000000000000000"
+    "00000112146357261fa893e4be85f78178babaca876f9a1fac18012240d1ed64"
+    "9964018377641a78638f0c19d3c346c1eb1a47e856c0fcd87d3fc72ff98172f9"
+    "39fc18ffdd16af746144279e6debb3f4913f491c69d22f6703e554510a280132"
+    "200295183aaa021cad737db7ddbc075964496ece1c0bcc1009bdae6d1799c83c"
+    "d4"
+);
+
 fn generate_message() -> Message {
     // Build CastId for the FrameActionBody.
     let cast_id = CastId {
@@ -43,31 +76,429 @@ fn generate_message() -> Message {
         body: Some(message_data::Body::FrameActionBody(frame_action_body)),
     };
 
-    // Build the top-level Message.
-    Message {
-        data: Some(message_data),
-        // Decode hex "0x6357261fa893e4be85f78178babaca876f9a1fac"
-        hash: hex!("6357261fa893e4be85f78178babaca876f9a1fac").to_vec(),
-        // HASH_SCHEME_BLAKE3 is value 1.
-        hash_scheme: HashScheme::Blake3 as i32,
-        // Decode the base64 signature.
-        signature: BASE64_STANDARD.decode("0e1kmWQBg3dkGnhjjwwZ08NGwesaR+hWwPzYfT/HL/mBcvk5/Bj/3RavdGFEJ55t67P0kT9JHGnSL2cD5VRRCg==")
-            .expect("Invalid base64 signature"),
-        // SIGNATURE_SCHEME_ED25519 is value 1.
-        signature_scheme: SignatureScheme::Ed25519 as i32,
-        // Decode hex for signer.
-        signer: hex!("0295183aaa021cad737db7ddbc075964496ece1c0bcc1009bdae6d1799c83cd4").to_vec(),
-        // In this example, no alternate data_bytes are provided.
-        data_bytes: None,
-    }
+    // Hash the data and sign the hash with the test key.
+    sign_message(message_data)
 }
 
 #[test]
 fn message_parsing_should_work() {
-    let message = generate_message();
-    let raw = message.encode();
-    let msg = parse_message(raw.clone());
+    let msg = parse_message(MESSAGE_BYTES.to_vec());
     assert!(msg.is_ok(), "Expected parse_message to succeed");
+    assert_eq!(msg.unwrap().encode(), generate_message().encode());
+}
+
+#[test]
+fn scale_message_parsing_should_work() {
+    let message = generate_message();
+    let msg = parse_scale_message(message.encode());
+    assert!(msg.is_ok(), "Expected parse_scale_message to succeed");
+    assert_eq!(msg.unwrap().encode(), message.encode());
+}
+
+#[test]
+fn message_parsing_should_check_the_network() {
+    let msg = parse_message_for_network(MESSAGE_BYTES.to_vec(), FarcasterNetwork::Mainnet);
+    assert_eq!(msg.unwrap().encode(), generate_message().encode());
+
+    let msg = parse_message_for_network(MESSAGE_BYTES.to_vec(), FarcasterNetwork::Testnet);
+    assert!(matches!(msg, Err(Error::WrongNetwork)));
+
+    let mut message = generate_message();
+    let mut data = message.data.take().unwrap();
+    data.network = FarcasterNetwork::None as i32;
+    message.data_bytes = Some(data.encode_proto());
+    let msg = parse_message_for_network(message.encode_proto(), FarcasterNetwork::Mainnet);
+    assert!(matches!(msg, Err(Error::InvalidNetwork)));
+}
+
+#[test]
+fn message_types_should_be_comparable_and_described() {
+    let message = parse_message(MESSAGE_BYTES.to_vec()).unwrap();
+    assert_eq!(message, generate_message());
+    assert_eq!(message.clone(), message);
+
+    let registry = {
+        let mut registry = scale_info::Registry::new();
+        registry.register_type(&scale_info::meta_type::<Message>());
+        scale_info::PortableRegistry::from(registry)
+    };
+    assert!(registry
+        .types
+        .iter()
+        .any(|ty| ty.ty.path.segments.last().map(|s| s.as_str()) == Some("FrameActionBody")));
+}
+
+#[test]
+fn bounded_types_should_round_trip() {
+    use bounded::{BoundedFrameActionBody, BoundedMessage, ProtocolFrameActionData};
+    use bounded_collections::ConstU32;
+    use parity_scale_codec::MaxEncodedLen;
+
+    type FrameAction =
+        BoundedFrameActionBody<ConstU32<256>, ConstU32<256>, ConstU32<4096>, ConstU32<256>>;
+
+    let message = generate_message();
+    let stored = BoundedMessage::<ConstU32<1024>>::try_from(&message).unwrap();
+    assert_eq!(stored.0.to_vec(), MESSAGE_BYTES.to_vec());
+    assert_eq!(stored.to_message(), Ok(message.clone()));
+    assert_eq!(
+        BoundedMessage::<ConstU32<224>>::try_from(&message),
+        Err(Error::TooLong)
+    );
+
+    let data = message.data.clone().unwrap();
+    let bounded = ProtocolFrameActionData::try_from(data.clone()).unwrap();
+    assert_eq!(bounded.fid, 289309);
+    assert!(bounded.encode().len() <= ProtocolFrameActionData::max_encoded_len());
+    assert_eq!(MessageData::from(bounded.clone()), data);
+    assert_eq!(bounded.clone(), bounded);
+
+    let mut cast = data.clone();
+    cast.r#type = MessageType::CastAdd as i32;
+    assert_eq!(
+        ProtocolFrameActionData::try_from(cast),
+        Err(Error::UnexpectedMessageType)
+    );
+    let mut no_body = data;
+    no_body.body = None;
+    assert_eq!(
+        ProtocolFrameActionData::try_from(no_body),
+        Err(Error::MissingBody)
+    );
+
+    let Some(message_data::Body::FrameActionBody(body)) = message.data.unwrap().body else {
+        panic!("fixture is a frame action");
+    };
+    let bounded = FrameAction::try_from(body.clone()).unwrap();
+    assert_eq!(bounded.cast_id.unwrap().fid, 289309);
+    assert!(bounded.encode().len() <= FrameAction::max_encoded_len());
+    assert_eq!(FrameActionBody::from(bounded), body);
+
+    let mut long = body.clone();
+    long.state = vec![0; 4097];
+    assert_eq!(FrameAction::try_from(long), Err(Error::TooLong));
+
+    let mut bad_cast = body;
+    bad_cast.cast_id.as_mut().unwrap().hash.pop();
+    assert_eq!(FrameAction::try_from(bad_cast), Err(Error::InvalidHash));
+}
+
+#[test]
+fn bounded_bodies_should_round_trip() {
+    use bounded::*;
+    use parity_scale_codec::MaxEncodedLen;
+
+    fn round_trip<B, T>(body: T)
+    where
+        T: Clone + PartialEq + core::fmt::Debug + TryInto<B, Error = Error>,
+        B: Into<T> + Encode + MaxEncodedLen,
+    {
+        let bounded: B = body.clone().try_into().unwrap();
+        assert!(bounded.encode().len() <= B::max_encoded_len());
+        assert_eq!(bounded.into(), body);
+    }
+
+    let cast_id = CastId {
+        fid: 2,
+        hash: vec![1; 20],
+    };
+    let cast = CastAddBody {
+        embeds_deprecated: vec!["https://example.com/a".into()],
+        mentions: vec![3, 4],
+        parent: Some(cast_add_body::Parent::ParentCastId(cast_id.clone())),
+        text: "gm @a @b".into(),
+        mentions_positions: vec![3, 6],
+        embeds: vec![
+            Embed {
+                embed: Some(embed::Embed::Url("https://example.com/b".into())),
+            },
+            Embed { embed: None },
+        ],
+        r#type: CastType::LongCast as i32,
+    };
+    round_trip::<BoundedCastAddBody, _>(cast.clone());
+    round_trip::<BoundedCastRemoveBody, _>(CastRemoveBody {
+        target_hash: vec![1; 20],
+    });
+    round_trip::<BoundedReactionBody, _>(ReactionBody {
+        r#type: ReactionType::Like as i32,
+        target: Some(reaction_body::Target::TargetUrl(
+            "https://example.com".into(),
+        )),
+    });
+    round_trip::<BoundedVerificationAddAddressBody, _>(VerificationAddAddressBody {
+        address: vec![5; 20],
+        claim_signature: vec![6; 65],
+        block_hash: vec![7; 32],
+        verification_type: 0,
+        chain_id: 0,
+        protocol: Protocol::Ethereum as i32,
+    });
+    round_trip::<BoundedVerificationRemoveBody, _>(VerificationRemoveBody {
+        address: vec![8; 32],
+        protocol: Protocol::Solana as i32,
+    });
+    round_trip::<BoundedUserDataBody, _>(UserDataBody {
+        r#type: UserDataType::Bio as i32,
+        value: "gm".into(),
+    });
+    round_trip::<BoundedLinkBody, _>(LinkBody {
+        r#type: "follow".into(),
+        display_timestamp: Some(TIMESTAMP),
+        target: Some(link_body::Target::TargetFid(3)),
+    });
+    round_trip::<BoundedUserNameProof, _>(UserNameProof {
+        timestamp: 1_709_198_032,
+        name: b"alice".to_vec(),
+        owner: vec![9; 20],
+        signature: vec![10; 65],
+        fid: 2,
+        r#type: UserNameType::UsernameTypeFname as i32,
+    });
+
+    let mut long = cast.clone();
+    long.text = "a".repeat(1025);
+    assert_eq!(BoundedCastAddBody::try_from(long), Err(Error::TooLong));
+    let mut crowded = cast.clone();
+    crowded.embeds_deprecated = vec![String::new(); 3];
+    assert_eq!(BoundedCastAddBody::try_from(crowded), Err(Error::TooLong));
+    let mut bad_parent = cast;
+    bad_parent.parent = Some(cast_add_body::Parent::ParentCastId(CastId {
+        fid: 2,
+        hash: vec![1; 19],
+    }));
+    assert_eq!(
+        BoundedCastAddBody::try_from(bad_parent),
+        Err(Error::InvalidHash)
+    );
+    assert_eq!(
+        BoundedLinkBody::try_from(LinkBody {
+            r#type: "123456789".into(),
+            ..Default::default()
+        }),
+        Err(Error::TooLong)
+    );
+}
+
+#[test]
+fn proto_message_encoding_should_work() {
+    let message = generate_message();
+
+    let raw = encode_proto_message(&message);
+
+    assert!(raw.is_ok(), "Expected encode_proto_message to succeed");
+    assert_eq!(raw.unwrap(), MESSAGE_BYTES.to_vec());
+}
+
+#[test]
+fn protobuf_decoding_should_accept_packed_and_unpacked_fields() {
+    // A CastAdd with packed mentions, unpacked mention positions and an unknown field 99.
+    let raw = hex!("080110032a16120303ac022804280922086869204061204062980607");
+    let data = MessageData::decode_proto(&raw).expect("Expected MessageData to decode");
+
+    assert_eq!(data.r#type, MessageType::CastAdd as i32);
+    assert_eq!(data.fid, 3);
+    let Some(message_data::Body::CastAddBody(body)) = data.body else {
+        panic!("Expected a CastAddBody");
+    };
+    assert_eq!(body.mentions, vec![3, 300]);
+    assert_eq!(body.mentions_positions, vec![4, 9]);
+    assert_eq!(body.text, "hi @a @b");
+}
+
+#[test]
+fn protobuf_decoding_should_reject_malformed_input() {
+    // Truncated length-delimited field.
+    assert_eq!(
+        Message::decode_proto(&MESSAGE_BYTES[..20]).err(),
+        Some(DecodeError::UnexpectedEof)
+    );
+    // `hash` sent as a varint instead of bytes.
+    assert_eq!(
+        Message::decode_proto(&hex!("1001")).err(),
+        Some(DecodeError::InvalidWireType)
+    );
+    // Field number zero.
+    assert_eq!(
+        Message::decode_proto(&hex!("0001")).err(),
+        Some(DecodeError::InvalidKey)
+    );
+    // Invalid UTF-8 in `CastAddBody.text`.
+    assert_eq!(
+        CastAddBody::decode_proto(&hex!("2202c328")).err(),
+        Some(DecodeError::InvalidUtf8)
+    );
+}
+
+#[test]
+fn protobuf_decoding_should_enforce_limits() {
+    use limits::*;
+
+    let frame_action = |body: FrameActionBody| {
+        let mut data = generate_message().data.unwrap();
+        data.body = Some(message_data::Body::FrameActionBody(body));
+        let message = Message {
+            data: Some(data),
+            ..Default::default()
+        };
+        parse_message(message.encode_proto())
+    };
+    let state = |len| FrameActionBody {
+        state: vec![0; len],
+        ..Default::default()
+    };
+    assert!(frame_action(state(MAX_FRAME_STATE_LENGTH as usize)).is_ok());
+    assert_eq!(
+        frame_action(state(MAX_FRAME_STATE_LENGTH as usize + 1)).err(),
+        Some(Error::TooLong)
+    );
+
+    // Packed mentions are rejected as soon as the eleventh is read.
+    let mentions = CastAddBody {
+        mentions: (1..=11).collect(),
+        ..Default::default()
+    };
+    assert_eq!(
+        CastAddBody::decode_proto(&mentions.encode_proto()).err(),
+        Some(DecodeError::LimitExceeded)
+    );
+
+    // Decoding only applies the long cast limit; validation applies the one of the type.
+    let cast = |r#type: CastType| CastAddBody {
+        text: "a".repeat(MAX_CAST_TEXT_LENGTH as usize + 1),
+        r#type: r#type as i32,
+        ..Default::default()
+    };
+    let data = |body| MessageData {
+        body: Some(message_data::Body::CastAddBody(body)),
+        ..Default::default()
+    };
+    let short = CastAddBody::decode_proto(&cast(CastType::Cast).encode_proto()).unwrap();
+    assert_eq!(validate_limits(&data(short)), Err(Error::TooLong));
+    assert_eq!(validate_limits(&data(cast(CastType::LongCast))), Ok(()));
+}
+
+#[test]
+fn borrowed_message_view_should_work() {
+    use borrowed::MessageRef;
+
+    let message = generate_message();
+    let view = MessageRef::decode(&MESSAGE_BYTES).unwrap();
+    assert_eq!(view.hash, &message.hash[..]);
+    assert_eq!(view.signer, &message.signer[..]);
+    assert_eq!(view.data_bytes, None);
+    assert_eq!(view.to_message(), Ok(message.clone()));
+
+    let data = view.message_data().unwrap().unwrap();
+    assert_eq!(data.fid, 289309);
+    assert_eq!(data.timestamp, TIMESTAMP);
+    let frame_action = data.frame_action().unwrap();
+    assert_eq!(
+        frame_action.url,
+        b"https://pelican-fond-distinctly.ngrok-free.app/og"
+    );
+    assert_eq!(frame_action.button_index, 1);
+    assert_eq!(frame_action.cast_id.unwrap().fid, 289309);
+    let owned = message.data.clone().unwrap();
+    assert_eq!(data.to_message_data(), Ok(owned.clone()));
+    assert_eq!(
+        Some(message_data::Body::FrameActionBody(
+            frame_action.to_frame_action_body()
+        )),
+        owned.body
+    );
+
+    // `data_bytes` takes precedence, as it is what the hash covers.
+    let mut with_data_bytes = message;
+    with_data_bytes.data_bytes = Some(with_data_bytes.data.take().unwrap().encode_proto());
+    let raw = with_data_bytes.encode_proto();
+    let view = MessageRef::decode(&raw).unwrap();
+    assert_eq!(view.data, None);
+    assert_eq!(view.signed_data(), with_data_bytes.data_bytes.as_deref());
+    assert_eq!(view.message_data().unwrap().unwrap().fid, 289309);
+}
+
+#[test]
+fn borrowed_message_view_should_verify() {
+    use borrowed::MessageRef;
+
+    let view = MessageRef::decode(&HUB_MESSAGE_BYTES).unwrap();
+    assert_eq!(view.verify_hash(), Ok(()));
+    assert_eq!(view.verify_signature(), Ok(()));
+    let message = Message::try_from(view).unwrap();
+    assert_eq!(verify_message(&message), Ok(()));
+    let data = view.message_data().unwrap().unwrap();
+    assert_eq!(
+        MessageData::try_from(data),
+        Ok(message.data.clone().unwrap())
+    );
+    let Some(message_data::Body::FrameActionBody(body)) = message.data.clone().unwrap().body else {
+        panic!("hub message is a frame action");
+    };
+    assert_eq!(FrameActionBody::from(*data.frame_action().unwrap()), body);
+
+    // `data_bytes` is hashed in place.
+    let mut with_data_bytes = generate_message();
+    with_data_bytes.data_bytes = Some(with_data_bytes.data.take().unwrap().encode_proto());
+    let raw = with_data_bytes.encode_proto();
+    let view = MessageRef::decode(&raw).unwrap();
+    assert_eq!(view.verify_hash(), Ok(()));
+    assert_eq!(view.verify_signature(), Ok(()));
+    assert!(parse_frame_action(&raw, &frame_action_config()).is_ok());
+
+    // A `data` field encoded out of field order still verifies against its canonical encoding.
+    let mut message = generate_message();
+    let data = message.data.take().unwrap();
+    let canonical = data.encode_proto();
+    let mut reordered = vec![0x20, data.network as u8];
+    reordered.extend(MessageData { network: 0, ..data }.encode_proto());
+    let mut raw = vec![0x0a, reordered.len() as u8];
+    raw.extend(&reordered);
+    raw.extend(message.encode_proto());
+    let view = MessageRef::decode(&raw).unwrap();
+    assert_ne!(view.data, Some(&canonical[..]));
+    assert_eq!(view.verify_hash(), Ok(()));
+    assert!(parse_frame_action(&raw, &frame_action_config()).is_ok());
+
+    let mut tampered = generate_message();
+    tampered.signature[0] ^= 1;
+    let raw = tampered.encode_proto();
+    let view = MessageRef::decode(&raw).unwrap();
+    assert_eq!(view.verify_hash(), Ok(()));
+    assert_eq!(view.verify_signature(), Err(Error::InvalidSignature));
+    tampered.hash[0] ^= 1;
+    let raw = tampered.encode_proto();
+    assert_eq!(
+        MessageRef::decode(&raw).unwrap().verify_hash(),
+        Err(Error::HashMismatch)
+    );
+}
+
+#[test]
+fn borrowed_message_view_should_reject_duplicate_messages() {
+    use borrowed::{FrameActionRef, MessageRef};
+
+    // `data` twice: the owned decoder merges them, the view cannot.
+    assert_eq!(
+        MessageRef::decode(&hex!("0a0210010a021802")).err(),
+        Some(DecodeError::DuplicateField)
+    );
+    // `cast_id` twice.
+    assert_eq!(
+        FrameActionRef::decode(&hex!("1a0208011a021002")).err(),
+        Some(DecodeError::DuplicateField)
+    );
+    assert_eq!(
+        FrameActionRef::decode(
+            &[0x0a, 0x82, 0x02]
+                .iter()
+                .chain(&[b'a'; 258])
+                .copied()
+                .collect::<Vec<_>>()
+        )
+        .err(),
+        Some(DecodeError::LimitExceeded)
+    );
 }
 
 #[test]
@@ -89,3 +520,1461 @@ fn message_encoding_should_work() {
     assert!(raw.is_ok(), "Expected encode_message to succeed");
     assert_eq!(raw.unwrap(), message.encode());
 }
+
+#[test]
+fn protobuf_encoding_should_round_trip_hub_bytes() {
+    // A CastAdd as written by the hub: empty packed `mentions` and `mentions_positions`,
+    // and `parent_url` (field 7) ahead of `text` (field 4).
+    let raw = hex!(
+        "0801100318d0eccc2f20012a2912003a10636861696e3a2f2f6569703135353a"
+        "312202676d2a00320d0a0b68747470733a2f2f782e79"
+    );
+    let data = MessageData::decode_proto(&raw).expect("Expected MessageData to decode");
+    assert_eq!(data.encode_proto(), raw.to_vec());
+
+    let msg = parse_message(MESSAGE_BYTES.to_vec()).expect("Expected parse_message to succeed");
+    assert_eq!(encode_proto_message(&msg), Ok(MESSAGE_BYTES.to_vec()));
+}
+
+#[test]
+fn hash_verification_should_work() {
+    let mut message = generate_message();
+    let data = message.data.as_ref().unwrap().encode_proto();
+    assert_eq!(message.hash, compute_hash(&data));
+    assert_eq!(verify_hash(&message), Ok(()));
+
+    // `data_bytes` takes precedence over `data` when present.
+    message.data = None;
+    message.data_bytes = Some(data);
+    assert_eq!(verify_hash(&message), Ok(()));
+}
+
+#[test]
+fn hub_message_hash_should_verify() {
+    let msg = parse_message(HUB_MESSAGE_BYTES.to_vec()).expect("Expected parse_message to succeed");
+    assert_eq!(encode_proto_message(&msg), Ok(HUB_MESSAGE_BYTES.to_vec()));
+
+    let data = msg.data.as_ref().unwrap();
+    assert_eq!(data.fid, 289309);
+    assert_eq!(data.time().to_unix_seconds(), 1_709_198_032);
+    assert_eq!(
+        msg.hash,
+        hex!("6357261fa893e4be85f78178babaca876f9a1fac").to_vec()
+    );
+    assert_eq!(compute_hash(&data.encode_proto()).to_vec(), msg.hash);
+    assert_eq!(verify_hash(&msg), Ok(()));
+}
+
+#[test]
+fn hub_message_signature_should_verify() {
+    // The hash, signature and signer of a frame action as a hub returned them.
+    let msg = Message {
+        hash: hex!("6357261fa893e4be85f78178babaca876f9a1fac").to_vec(),
+        signature: BASE64_STANDARD
+            .decode("0e1kmWQBg3dkGnhjjwwZ08NGwesaR+hWwPzYfT/HL/mBcvk5/Bj/3RavdGFEJ55t67P0kT9JHGnSL2cD5VRRCg==")
+            .expect("Invalid base64 signature"),
+        signature_scheme: SignatureScheme::Ed25519 as i32,
+        signer: hex!("0295183aaa021cad737db7ddbc075964496ece1c0bcc1009bdae6d1799c83cd4").to_vec(),
+        ..Default::default()
+    };
+    assert_eq!(verify_signature(&msg), Ok(()));
+
+    let hub = parse_message(HUB_MESSAGE_BYTES.to_vec()).unwrap();
+    assert_eq!(
+        (&hub.hash, &hub.signature, &hub.signer),
+        (&msg.hash, &msg.signature, &msg.signer)
+    );
+
+    let mut tampered = msg;
+    tampered.hash[0] ^= 1;
+    assert_eq!(verify_signature(&tampered), Err(Error::InvalidSignature));
+}
+
+#[test]
+fn hash_verification_should_not_work() {
+    let mut message = generate_message();
+    message.hash[0] ^= 0x01;
+    assert_eq!(verify_hash(&message), Err(Error::HashMismatch));
+
+    message.hash_scheme = HashScheme::None as i32;
+    assert_eq!(verify_hash(&message), Err(Error::UnsupportedHashScheme));
+
+    message.hash_scheme = HashScheme::Blake3 as i32;
+    message.data = None;
+    assert_eq!(verify_hash(&message), Err(Error::MissingData));
+}
+
+#[test]
+fn signature_verification_should_work() {
+    let message = generate_message();
+    assert_eq!(verify_signature(&message), Ok(()));
+}
+
+#[test]
+fn signature_verification_should_not_work() {
+    let mut message = generate_message();
+    message.hash[0] ^= 0x01;
+    assert_eq!(verify_signature(&message), Err(Error::InvalidSignature));
+
+    let mut message = generate_message();
+    message.signature.pop();
+    assert_eq!(verify_signature(&message), Err(Error::InvalidSignature));
+
+    let mut message = generate_message();
+    message.signer.push(0);
+    assert_eq!(verify_signature(&message), Err(Error::InvalidSigner));
+
+    let mut message = generate_message();
+    message.signature_scheme = SignatureScheme::None as i32;
+    assert_eq!(
+        verify_signature(&message),
+        Err(Error::UnsupportedSignatureScheme)
+    );
+
+    // A valid signature does not make up for a hash that does not match the data.
+    let mut message = generate_message();
+    message.data.as_mut().unwrap().fid += 1;
+    assert_eq!(verify_signature(&message), Ok(()));
+    assert_eq!(verify_message(&message), Err(Error::HashMismatch));
+}
+
+// Private key from the eth-account documentation, owning 0x2c7536e3605d9c16a7a3d7b1898e529396a65c23.
+const ETH_KEY: [u8; 32] = hex!("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318");
+const ETH_ADDRESS: [u8; 20] = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+
+fn eth_sign(digest: &[u8; 32]) -> Vec<u8> {
+    let key = k256::ecdsa::SigningKey::from_slice(&ETH_KEY).unwrap();
+    let (signature, recovery_id) = key.sign_prehash_recoverable(digest).unwrap();
+    let mut bytes = signature.to_vec();
+    bytes.push(27 + recovery_id.to_byte());
+    bytes
+}
+
+#[test]
+fn eip712_digests_should_match_reference() {
+    // Reference digests computed with alloy's EIP-712 implementation.
+    assert_eq!(
+        eip712::message_hash_digest(&[0x11; 20]),
+        hex!("2a57b12738ab2f7ff56e2e1cd178f1fdd56ed0d36b9720d2c0d851697e16970b")
+    );
+    assert_eq!(
+        eip712::verification_claim_digest(289309, &[0x22; 20], &[0x33; 32], 1),
+        hex!("9836c9a3e0f3f65bf0f91c084ecfc566fe43334eb6e07f03524f45d34d4c525d")
+    );
+    assert_eq!(
+        eip712::username_proof_digest(b"alice", 1_700_000_000, &[0x44; 20]),
+        hex!("c82a48320d79ad4d7f3a2e031375bc62a8f760d70744a004e001fa5de1daa6de")
+    );
+}
+
+#[test]
+fn eip712_signature_verification_should_work() {
+    let mut message = generate_message();
+    message.signature_scheme = SignatureScheme::Eip712 as i32;
+    message.signer = ETH_ADDRESS.to_vec();
+    message.signature = eth_sign(&eip712::message_hash_digest(&message.hash));
+    assert_eq!(verify_signature(&message), Ok(()));
+
+    message.signer = [0x22; 20].to_vec();
+    assert_eq!(verify_signature(&message), Err(Error::InvalidSignature));
+
+    message.signer = [0x22; 32].to_vec();
+    assert_eq!(verify_signature(&message), Err(Error::InvalidSigner));
+}
+
+#[test]
+fn eip712_claim_verification_should_work() {
+    let block_hash = [0x33; 32];
+    let claim = eip712::verification_claim_digest(289309, &ETH_ADDRESS, &block_hash, 1);
+    let data = |block_hash: &[u8], verification_type: u32| MessageData {
+        r#type: MessageType::VerificationAddEthAddress as i32,
+        fid: 289309,
+        timestamp: TIMESTAMP,
+        network: FarcasterNetwork::Mainnet as i32,
+        body: Some(message_data::Body::VerificationAddAddressBody(
+            VerificationAddAddressBody {
+                address: ETH_ADDRESS.to_vec(),
+                claim_signature: eth_sign(&claim),
+                block_hash: block_hash.to_vec(),
+                verification_type,
+                ..Default::default()
+            },
+        )),
+    };
+    assert_eq!(
+        eip712::verify_verification_claim(&data(&block_hash, 0)),
+        Ok(())
+    );
+    assert_eq!(
+        eip712::verify_verification_claim(&data(&[0x44; 32], 0)),
+        Err(Error::InvalidSignature)
+    );
+    assert_eq!(
+        eip712::verify_verification_claim(&data(&block_hash, 1)),
+        Err(Error::UnsupportedVerification)
+    );
+
+    let proof = UserNameProof {
+        timestamp: 1_700_000_000,
+        name: b"alice".to_vec(),
+        owner: [0x44; 20].to_vec(),
+        signature: eth_sign(&eip712::username_proof_digest(
+            b"alice",
+            1_700_000_000,
+            &[0x44; 20],
+        )),
+        fid: 289309,
+        r#type: UserNameType::UsernameTypeFname as i32,
+    };
+    assert_eq!(eip712::verify_username_proof(&proof, &ETH_ADDRESS), Ok(()));
+    assert_eq!(
+        eip712::verify_username_proof(&proof, &[0x22; 20]),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn enum_conversion_should_work() {
+    // Every value the enum names converts back to itself; others are reported.
+    for value in -1..=20 {
+        match MessageType::try_from(value) {
+            Ok(message_type) => assert_eq!(i32::from(message_type), value),
+            Err(unknown) => assert_eq!(unknown, UnknownEnum(value)),
+        }
+    }
+    assert_eq!(MessageType::try_from(9), Err(UnknownEnum(9)));
+    assert_eq!(UserDataType::try_from(5), Ok(UserDataType::Url));
+    assert_eq!(
+        UserNameType::try_from(2),
+        Ok(UserNameType::UsernameTypeEnsL1)
+    );
+
+    let message = generate_message();
+    assert_eq!(message.hash_scheme(), Ok(HashScheme::Blake3));
+    assert_eq!(message.signature_scheme(), Ok(SignatureScheme::Ed25519));
+    let data = message.data.unwrap();
+    assert_eq!(data.message_type(), Ok(MessageType::FrameAction));
+    assert_eq!(data.network(), Ok(FarcasterNetwork::Mainnet));
+
+    let body = ReactionBody {
+        r#type: 7,
+        ..Default::default()
+    };
+    assert_eq!(body.reaction_type(), Err(UnknownEnum(7)));
+    assert_eq!(Error::from(UnknownEnum(7)), Error::InvalidMessage);
+}
+
+#[test]
+fn farcaster_time_conversion_should_work() {
+    use time::FARCASTER_EPOCH;
+
+    let time = generate_message().data.unwrap().time();
+    assert_eq!(time.to_unix_seconds(), 1_709_284_432);
+    assert_eq!(time.to_unix_millis(), 1_709_284_432_000);
+    assert_eq!(FarcasterTime::from_unix_seconds(1_709_284_432), Ok(time));
+    assert_eq!(FarcasterTime::from_unix_millis(1_709_284_432_999), Ok(time));
+
+    assert_eq!(
+        FarcasterTime::from_unix_seconds(FARCASTER_EPOCH),
+        Ok(FarcasterTime(0))
+    );
+    assert_eq!(
+        FarcasterTime::from_unix_seconds(FARCASTER_EPOCH - 1),
+        Err(Error::InvalidTimestamp)
+    );
+    assert_eq!(
+        FarcasterTime::from_unix_seconds(FARCASTER_EPOCH + u64::from(u32::MAX) + 1),
+        Err(Error::InvalidTimestamp)
+    );
+}
+
+fn frame_action_config() -> ValidationConfig<'static> {
+    ValidationConfig {
+        network: Some(FarcasterNetwork::Mainnet),
+        now: Some(FarcasterTime(TIMESTAMP)),
+        message_types: &[MessageType::FrameAction],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn message_validation_should_work() {
+    let message = generate_message();
+    assert_eq!(validate_message(&message, &frame_action_config()), Ok(()));
+
+    // Checks run against `data_bytes` when it is present.
+    let mut message = message;
+    message.data_bytes = Some(message.data.take().unwrap().encode_proto());
+    assert_eq!(validate_message(&message, &frame_action_config()), Ok(()));
+}
+
+#[test]
+fn body_validation_should_match_type_and_body() {
+    use validation::validate_body;
+
+    let with = |message_type: i32, body: Option<message_data::Body>| MessageData {
+        r#type: message_type,
+        body,
+        ..Default::default()
+    };
+    let reaction = || Some(message_data::Body::ReactionBody(ReactionBody::default()));
+
+    assert_eq!(
+        validate_body(&with(MessageType::ReactionAdd as i32, reaction())),
+        Ok(())
+    );
+    assert_eq!(
+        validate_body(&with(MessageType::ReactionRemove as i32, reaction())),
+        Ok(())
+    );
+    assert_eq!(
+        validate_body(&with(MessageType::CastRemove as i32, reaction())),
+        Err(Error::BodyMismatch)
+    );
+    assert_eq!(
+        validate_body(&with(MessageType::None as i32, reaction())),
+        Err(Error::BodyMismatch)
+    );
+    assert_eq!(
+        validate_body(&with(MessageType::ReactionAdd as i32, None)),
+        Err(Error::MissingBody)
+    );
+    assert_eq!(
+        validate_body(&with(99, reaction())),
+        Err(Error::InvalidMessage)
+    );
+
+    // `validate_message` runs the check even when every message type is accepted.
+    let mut data = generate_message().data.unwrap();
+    data.r#type = MessageType::CastAdd as i32;
+    assert_eq!(
+        validate_message(&sign_message(data), &ValidationConfig::default()),
+        Err(Error::BodyMismatch)
+    );
+}
+
+#[test]
+fn message_validation_should_report_the_failing_check() {
+    let config = frame_action_config();
+    let validate = |data: MessageData| validate_message(&sign_message(data), &config);
+
+    let mut data = generate_message().data.unwrap();
+    data.fid = 0;
+    assert_eq!(validate(data), Err(Error::InvalidFid));
+
+    let mut data = generate_message().data.unwrap();
+    data.network = FarcasterNetwork::Testnet as i32;
+    assert_eq!(validate(data), Err(Error::WrongNetwork));
+
+    let mut data = generate_message().data.unwrap();
+    data.network = FarcasterNetwork::None as i32;
+    assert_eq!(validate(data), Err(Error::InvalidNetwork));
+
+    let mut data = generate_message().data.unwrap();
+    data.timestamp = TIMESTAMP + MAX_FUTURE_DRIFT + 1;
+    assert_eq!(validate(data), Err(Error::TimestampInFuture));
+
+    let old = ValidationConfig {
+        max_age: Some(60),
+        ..config
+    };
+    let mut data = generate_message().data.unwrap();
+    data.timestamp = TIMESTAMP - 61;
+    assert_eq!(
+        validate_message(&sign_message(data), &old),
+        Err(Error::TimestampTooOld)
+    );
+
+    let mut data = generate_message().data.unwrap();
+    data.body = None;
+    assert_eq!(validate(data), Err(Error::MissingBody));
+
+    let mut data = generate_message().data.unwrap();
+    data.r#type = MessageType::CastAdd as i32;
+    assert_eq!(validate(data), Err(Error::UnexpectedMessageType));
+
+    let mut data = generate_message().data.unwrap();
+    data.body = Some(message_data::Body::CastAddBody(CastAddBody::default()));
+    assert_eq!(validate(data), Err(Error::BodyMismatch));
+
+    // The signature is valid but the hash no longer matches the data.
+    let mut message = generate_message();
+    message.data.as_mut().unwrap().fid += 1;
+    assert_eq!(
+        validate_message(&message, &config),
+        Err(Error::HashMismatch)
+    );
+
+    let mut message = generate_message();
+    message.signature[0] ^= 0x01;
+    assert_eq!(
+        validate_message(&message, &config),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn message_builder_should_sign_valid_messages() {
+    use builder::{MessageBuilder, SigningKey};
+
+    let key = SigningKey::from_bytes(&SIGNER_KEY);
+    let message =
+        || MessageBuilder::new(289309, FarcasterNetwork::Mainnet, FarcasterTime(TIMESTAMP));
+    let cast = || {
+        reaction_body::Target::TargetCastId(CastId {
+            fid: 3,
+            hash: vec![1; 20],
+        })
+    };
+    let Some(message_data::Body::FrameActionBody(frame_action)) =
+        generate_message().data.unwrap().body
+    else {
+        panic!("fixture is a frame action");
+    };
+    let built = [
+        message().cast_add(CastType::Cast, "gm", &[(3, 0)], vec![], None),
+        message().cast_add(
+            CastType::LongCast,
+            &"a".repeat(1024),
+            &[],
+            vec![embed::Embed::Url("https://example.com".into())],
+            Some(cast_add_body::Parent::ParentCastId(CastId {
+                fid: 3,
+                hash: vec![1; 20],
+            })),
+        ),
+        message().cast_remove([1; 20]),
+        message().reaction_add(ReactionType::Like, cast()),
+        message().reaction_remove(ReactionType::Recast, cast()),
+        message().link_add("follow", 3),
+        message().link_remove("follow", 3),
+        message().link_compact_state("follow", vec![3, 4]),
+        message().verification_add_address(
+            Protocol::Ethereum,
+            ETH_ADDRESS.to_vec(),
+            vec![1; 65],
+            vec![2; 32],
+            None,
+        ),
+        message().verification_remove(ETH_ADDRESS.to_vec(), Protocol::Ethereum),
+        message().user_data_add(UserDataType::Display, "Alice"),
+        message().username_proof(
+            UserNameType::UsernameTypeFname,
+            "alice",
+            ETH_ADDRESS,
+            vec![3; 65],
+            1_709_198_032,
+        ),
+        message().frame_action(frame_action.clone()),
+    ];
+    let config = ValidationConfig {
+        network: Some(FarcasterNetwork::Mainnet),
+        now: Some(FarcasterTime(TIMESTAMP)),
+        ..Default::default()
+    };
+    for builder in built {
+        let message_type = builder.data().message_type().unwrap();
+        let msg = builder.sign(&key).unwrap();
+        assert_eq!(validate_message(&msg, &config), Ok(()), "{message_type:?}");
+        assert_eq!(parse_message(encode_proto_message(&msg).unwrap()), Ok(msg));
+    }
+
+    // The fixture is the builder's frame action, signed with the test key.
+    let msg = message().frame_action(frame_action).sign(&key).unwrap();
+    assert_eq!(msg, generate_message());
+    assert_eq!(msg.signer, key.verifying_key().to_bytes());
+
+    // Typed parameters land in the raw fields.
+    let data = message()
+        .cast_add(CastType::LongCast, "gm @", &[(3, 3), (4, 3)], vec![], None)
+        .data()
+        .clone();
+    let Some(message_data::Body::CastAddBody(cast)) = data.body else {
+        panic!("built a cast");
+    };
+    assert_eq!(cast.cast_type(), Ok(CastType::LongCast));
+    assert_eq!(
+        (cast.mentions, cast.mentions_positions),
+        (vec![3, 4], vec![3, 3])
+    );
+    let data = message()
+        .verification_add_address(
+            Protocol::Solana,
+            vec![5; 32],
+            vec![6; 64],
+            vec![7; 32],
+            Some(8453),
+        )
+        .data()
+        .clone();
+    let Some(message_data::Body::VerificationAddAddressBody(verification)) = data.body else {
+        panic!("built a verification");
+    };
+    assert_eq!(verification.protocol(), Ok(Protocol::Solana));
+    assert_eq!(
+        (verification.verification_type, verification.chain_id),
+        (1, 8453)
+    );
+    let data = message()
+        .username_proof(
+            UserNameType::UsernameTypeEnsL1,
+            "alice.eth",
+            ETH_ADDRESS,
+            vec![],
+            0,
+        )
+        .data()
+        .clone();
+    let Some(message_data::Body::UsernameProofBody(proof)) = data.body else {
+        panic!("built a username proof");
+    };
+    assert_eq!(proof.username_type(), Ok(UserNameType::UsernameTypeEnsL1));
+    assert_eq!(proof.fid, 289309);
+
+    assert_eq!(message().sign(&key), Err(Error::MissingBody));
+}
+
+#[test]
+fn frame_action_parsing_should_work() {
+    let message = generate_message();
+    let raw = encode_proto_message(&message).unwrap();
+    let action = parse_frame_action(&raw, &frame_action_config()).unwrap();
+    assert_eq!(action.fid, 289309);
+    assert_eq!(
+        action.url,
+        "https://pelican-fond-distinctly.ngrok-free.app/og"
+    );
+    assert_eq!(action.button_index, 1);
+    assert_eq!(action.cast_id.map(|cast_id| cast_id.fid), Some(289309));
+    assert_eq!(action.timestamp, FarcasterTime(TIMESTAMP));
+    assert_eq!(action.network, FarcasterNetwork::Mainnet);
+    assert_eq!(action.hash[..], message.hash[..]);
+    assert_eq!(action.signer, message.signer);
+
+    // Only frame actions are accepted, whatever the config allows.
+    let mut data = generate_message().data.unwrap();
+    data.r#type = MessageType::CastAdd as i32;
+    data.body = Some(message_data::Body::CastAddBody(CastAddBody::default()));
+    let raw = encode_proto_message(&sign_message(data)).unwrap();
+    assert_eq!(
+        parse_frame_action(&raw, &ValidationConfig::default()),
+        Err(Error::UnexpectedMessageType)
+    );
+
+    let mut data = generate_message().data.unwrap();
+    if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+        body.url = vec![0xff, 0xfe];
+    }
+    let raw = encode_proto_message(&sign_message(data)).unwrap();
+    assert_eq!(
+        parse_frame_action(&raw, &frame_action_config()),
+        Err(Error::InvalidUrl)
+    );
+
+    let mut message = generate_message();
+    message.data.as_mut().unwrap().timestamp += 1;
+    let raw = encode_proto_message(&message).unwrap();
+    assert_eq!(
+        parse_frame_action(&raw, &frame_action_config()),
+        Err(Error::HashMismatch)
+    );
+}
+
+#[test]
+fn frame_action_validation_should_work() {
+    use frame_action::validate_frame_action;
+
+    let body = |url: &str, button_index: u32| FrameActionBody {
+        url: url.as_bytes().to_vec(),
+        button_index,
+        ..Default::default()
+    };
+    let validate =
+        |url: &str, button_index: u32| validate_frame_action(&body(url, button_index), &[]);
+
+    assert_eq!(validate("https://frames.example.com", 1), Ok(()));
+    assert_eq!(validate("http://localhost:3000/frame?x=1", 4), Ok(()));
+    assert_eq!(validate("HTTPS://frames.example.com/", 2), Ok(()));
+    assert_eq!(
+        validate("https://frames.example.com/", 0),
+        Err(Error::InvalidButtonIndex)
+    );
+    assert_eq!(
+        validate("https://frames.example.com/", 5),
+        Err(Error::InvalidButtonIndex)
+    );
+    for url in [
+        "",
+        "frames.example.com",
+        "ftp://frames.example.com/",
+        "https://",
+        "https:///frame",
+        "https://frames.example.com/a b",
+        "javascript:alert(1)",
+    ] {
+        assert_eq!(validate(url, 1), Err(Error::InvalidUrl), "{url}");
+    }
+    let mut invalid = body("https://frames.example.com/", 1);
+    invalid.url.push(0xff);
+    assert_eq!(validate_frame_action(&invalid, &[]), Err(Error::InvalidUrl));
+
+    let long = "https://frames.example.com/".to_owned() + &"a".repeat(230);
+    assert_eq!(validate(&long, 1), Err(Error::TooLong));
+    let mut input = body("https://frames.example.com/", 1);
+    input.input_text = vec![b'a'; 257];
+    assert_eq!(validate_frame_action(&input, &[]), Err(Error::TooLong));
+
+    let origins = ["https://frames.example.com", "http://localhost:3000"];
+    let allowed = |url: &str| validate_frame_action(&body(url, 1), &origins);
+    assert_eq!(allowed("https://FRAMES.example.com/vote"), Ok(()));
+    assert_eq!(allowed("http://localhost:3000"), Ok(()));
+    assert_eq!(
+        allowed("http://frames.example.com/"),
+        Err(Error::UrlNotAllowed)
+    );
+    assert_eq!(allowed("http://localhost:3001/"), Err(Error::UrlNotAllowed));
+    assert_eq!(
+        allowed("https://frames.example.com.evil.example/"),
+        Err(Error::UrlNotAllowed)
+    );
+    assert_eq!(
+        allowed("https://frames.example.com@evil.example/"),
+        Err(Error::UrlNotAllowed)
+    );
+
+    // `validate_message` applies the checks to frame actions.
+    let mut data = generate_message().data.unwrap();
+    if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+        body.button_index = 5;
+    }
+    assert_eq!(
+        validate_message(&sign_message(data), &frame_action_config()),
+        Err(Error::InvalidButtonIndex)
+    );
+    let config = ValidationConfig {
+        frame_origins: &["https://frames.example.com"],
+        ..frame_action_config()
+    };
+    assert_eq!(
+        validate_message(&generate_message(), &config),
+        Err(Error::UrlNotAllowed)
+    );
+}
+
+#[test]
+fn url_prefix_matching_should_respect_origins_and_segments() {
+    use frame_action::url_has_prefix;
+
+    let prefix = "https://mint.example.com/nft";
+    assert!(url_has_prefix("https://mint.example.com/nft", prefix));
+    assert!(url_has_prefix("https://mint.example.com/nft/1", prefix));
+    assert!(url_has_prefix("https://MINT.example.com/nft?id=1", prefix));
+    assert!(!url_has_prefix("https://mint.example.com/nfts", prefix));
+    assert!(!url_has_prefix("https://mint.example.com/", prefix));
+
+    for url in [
+        "https://mint.example.com.evil/nft",
+        "https://mint.example.community/nft",
+        "https://mint.example.com:8443/nft",
+        "https://mint.example.com@evil.example/nft",
+        "http://mint.example.com/nft",
+        "not a url",
+    ] {
+        assert!(!url_has_prefix(url, prefix), "{url}");
+    }
+
+    assert!(url_has_prefix(
+        "https://mint.example.com",
+        "https://mint.example.com"
+    ));
+    assert!(url_has_prefix(
+        "https://mint.example.com/a",
+        "https://mint.example.com/"
+    ));
+    assert!(!url_has_prefix(
+        "https://mint.example.com.evil/",
+        "https://mint.example.com"
+    ));
+    assert!(!url_has_prefix("https://mint.example.com/", ""));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn frame_request_parsing_should_work() {
+    use frame_request::{parse_frame_request, FrameRequestError, UntrustedField};
+
+    let message = generate_message();
+    let request = |untrusted: &str| {
+        format!(
+            r#"{{"untrustedData":{{{untrusted}}},"trustedData":{{"messageBytes":"{}"}}}}"#,
+            hex::encode(encode_proto_message(&message).unwrap())
+        )
+    };
+    let untrusted = format!(
+        r#""fid":289309,"url":"https://pelican-fond-distinctly.ngrok-free.app/og","messageHash":"0x{}","timestamp":1709284432123,"network":1,"buttonIndex":1,"inputText":"","castId":{{"fid":289309,"hash":"0x0000000000000000000000000000000000000001"}}"#,
+        hex::encode(&message.hash)
+    );
+
+    let action = parse_frame_request(request(&untrusted).as_bytes(), &frame_action_config());
+    assert_eq!(
+        action,
+        parse_frame_action(
+            &encode_proto_message(&message).unwrap(),
+            &frame_action_config()
+        )
+        .map_err(FrameRequestError::from)
+    );
+
+    // Every disagreeing field is reported.
+    let forged = untrusted
+        .replace("289309,\"url\"", "1,\"url\"")
+        .replace("\"buttonIndex\":1", "\"buttonIndex\":2")
+        .replace(
+            "0000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000002",
+        )
+        + r#","state":"forged""#;
+    assert_eq!(
+        parse_frame_request(request(&forged).as_bytes(), &frame_action_config()),
+        Err(FrameRequestError::Mismatch(vec![
+            UntrustedField::Fid,
+            UntrustedField::ButtonIndex,
+            UntrustedField::CastId,
+            UntrustedField::State,
+        ]))
+    );
+
+    let late = untrusted.replace("1709284432123", "1709284433000");
+    assert_eq!(
+        parse_frame_request(request(&late).as_bytes(), &frame_action_config()),
+        Err(FrameRequestError::Mismatch(vec![UntrustedField::Timestamp]))
+    );
+
+    let config = ValidationConfig {
+        network: Some(FarcasterNetwork::Testnet),
+        ..frame_action_config()
+    };
+    assert_eq!(
+        parse_frame_request(request(&untrusted).as_bytes(), &config),
+        Err(FrameRequestError::Message(Error::WrongNetwork))
+    );
+
+    let bad_bytes = request(&untrusted).replace("\"messageBytes\":\"", "\"messageBytes\":\"zz");
+    assert_eq!(
+        parse_frame_request(bad_bytes.as_bytes(), &frame_action_config()),
+        Err(FrameRequestError::InvalidMessageBytes)
+    );
+    assert_eq!(
+        parse_frame_request(b"not json", &frame_action_config()),
+        Err(FrameRequestError::InvalidJson)
+    );
+}
+
+#[test]
+fn frame_meta_tags_should_round_trip() {
+    use frame::{AspectRatio, ButtonAction, Frame, FrameButton, FrameError};
+
+    let frame = Frame {
+        image: "https://frames.example.com/poll.png?q=\"yes\"&r=1".into(),
+        aspect_ratio: Some(AspectRatio::Square),
+        buttons: vec![
+            FrameButton {
+                label: "Vote <yes>".into(),
+                ..Default::default()
+            },
+            FrameButton {
+                label: "Results".into(),
+                action: ButtonAction::PostRedirect,
+                post_url: Some("https://frames.example.com/results".into()),
+                ..Default::default()
+            },
+            FrameButton {
+                label: "Mint".into(),
+                action: ButtonAction::Mint,
+                target: Some("eip155:8453:0x0000000000000000000000000000000000000001:1".into()),
+                ..Default::default()
+            },
+        ],
+        input_text: Some("Why?".into()),
+        state: Some("{\"round\":1}".into()),
+        post_url: Some("https://frames.example.com/vote".into()),
+    };
+    let html = frame.to_html().unwrap();
+    assert!(html.contains(r#"<meta property="fc:frame" content="vNext" />"#));
+    assert!(html.contains(r#"<meta property="og:image" content="https://frames.example.com/poll.png?q=&quot;yes&quot;&amp;r=1" />"#));
+    assert_eq!(Frame::from_html(&html), Ok(frame.clone()));
+
+    // Buttons share `FrameActionBody.button_index` numbering.
+    let action = generate_message().data.unwrap();
+    let Some(message_data::Body::FrameActionBody(body)) = action.body else {
+        panic!("fixture is a frame action");
+    };
+    assert_eq!(frame.button(body.button_index).unwrap().label, "Vote <yes>");
+    assert_eq!(frame.button(0), None);
+    assert_eq!(frame.button(4), None);
+
+    // Pages written by hand use `name`, single quotes and any attribute order.
+    let page = "<html><head><META name='fc:frame' content='vNext'>\n\
+        <meta content=\"https://x.example/a.png\" property=\"fc:frame:image\"/>\n\
+        <meta property=\"fc:frame:button:1\" content=\"Go\" >\n\
+        <meta property=\"fc:frame:button:1:action\" content=\"link\">\n\
+        <meta property=\"fc:frame:button:1:target\" content=\"https://x.example\">\n\
+        </head><body>ignored</body></html>";
+    let parsed = Frame::from_html(page).unwrap();
+    assert_eq!(parsed.image, "https://x.example/a.png");
+    assert_eq!(parsed.buttons[0].action, ButtonAction::Link);
+    assert_eq!(
+        parsed.buttons[0].target.as_deref(),
+        Some("https://x.example")
+    );
+
+    let mut invalid = frame.clone();
+    invalid.buttons[2].target = None;
+    assert_eq!(invalid.to_html(), Err(FrameError::MissingTarget(3)));
+    let mut invalid = frame.clone();
+    invalid.buttons.resize(5, FrameButton::default());
+    assert_eq!(invalid.to_html(), Err(FrameError::TooManyButtons));
+
+    // Clients reject frames over the length limits, so neither side produces or accepts them.
+    let long_url = "https://frames.example.com/".to_owned() + &"a".repeat(230);
+    let mut invalid = frame.clone();
+    invalid.post_url = Some(long_url.clone());
+    assert_eq!(invalid.to_html(), Err(FrameError::TooLong));
+    let mut invalid = frame.clone();
+    invalid.buttons[1].post_url = Some(long_url.clone());
+    assert_eq!(invalid.to_html(), Err(FrameError::TooLong));
+    let mut invalid = frame.clone();
+    invalid.buttons[2].target = Some(long_url.clone());
+    assert_eq!(invalid.to_html(), Err(FrameError::TooLong));
+    let mut invalid = frame.clone();
+    invalid.state = Some("s".repeat(4097));
+    assert_eq!(invalid.to_html(), Err(FrameError::TooLong));
+    let mut invalid = frame.clone();
+    invalid.input_text = Some("a".repeat(33));
+    assert_eq!(invalid.to_html(), Err(FrameError::TooLong));
+    let mut limit = frame.clone();
+    limit.input_text = Some("a".repeat(32));
+    limit.state = Some("s".repeat(4096));
+    assert!(limit.to_html().is_ok());
+    let long = html.replace("https://frames.example.com/vote", &long_url);
+    assert_eq!(Frame::from_html(&long), Err(FrameError::TooLong));
+
+    let gap = html.replace("fc:frame:button:2\"", "fc:frame:button:x\"");
+    assert_eq!(Frame::from_html(&gap), Err(FrameError::MissingButton(2)));
+    let unknown = html.replace("post_redirect", "redirect");
+    assert_eq!(
+        Frame::from_html(&unknown),
+        Err(FrameError::InvalidButtonAction)
+    );
+    assert_eq!(
+        Frame::from_html(&html.replace("vNext", "v1")),
+        Err(FrameError::InvalidVersion)
+    );
+    assert_eq!(
+        Frame::from_html(r#"<meta property="fc:frame" content="vNext">"#),
+        Err(FrameError::MissingImage)
+    );
+}
+
+#[test]
+fn frame_state_should_round_trip() {
+    use frame_state::{FrameState, StateError, STATE_MAC_LENGTH};
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct Poll {
+        round: u32,
+        votes: Vec<u64>,
+    }
+
+    let poll = Poll {
+        round: 2,
+        votes: vec![289309, 3],
+    };
+    let plain = FrameState::<Poll>::new();
+    let encoded = plain.encode(&poll).unwrap();
+    assert_eq!(encoded, hex::encode(poll.encode()));
+    assert_eq!(plain.decode(encoded.as_bytes()), Ok(poll));
+
+    let signed = FrameState::<Poll>::with_key([1; 32]);
+    let poll = plain.decode(encoded.as_bytes()).unwrap();
+    let encoded = signed.encode(&poll).unwrap();
+    assert_eq!(encoded.len(), (poll.encoded_size() + STATE_MAC_LENGTH) * 2);
+    assert_eq!(signed.decode(encoded.as_bytes()), Ok(poll));
+
+    // State changed by the client, or signed under another key, is rejected.
+    let mut tampered = encoded.clone().into_bytes();
+    tampered[0] = if tampered[0] == b'0' { b'1' } else { b'0' };
+    assert_eq!(signed.decode(&tampered), Err(StateError::InvalidMac));
+    assert_eq!(
+        FrameState::<Poll>::with_key([2; 32]).decode(encoded.as_bytes()),
+        Err(StateError::InvalidMac)
+    );
+    assert_eq!(signed.decode(b"00"), Err(StateError::InvalidMac));
+    assert_eq!(
+        signed.decode(
+            plain
+                .encode(&Poll {
+                    round: 1,
+                    votes: vec![]
+                })
+                .unwrap()
+                .as_bytes()
+        ),
+        Err(StateError::InvalidMac)
+    );
+
+    assert_eq!(plain.decode(b"0x01"), Err(StateError::InvalidEncoding));
+    assert_eq!(plain.decode(b"020"), Err(StateError::InvalidEncoding));
+    assert_eq!(plain.decode(b"02"), Err(StateError::InvalidState));
+    // Trailing bytes are not ignored.
+    let trailing = plain
+        .encode(&Poll {
+            round: 1,
+            votes: vec![],
+        })
+        .unwrap()
+        + "00";
+    assert_eq!(
+        plain.decode(trailing.as_bytes()),
+        Err(StateError::InvalidState)
+    );
+
+    let big = Poll {
+        round: 0,
+        votes: vec![0; 256],
+    };
+    assert_eq!(plain.encode(&big), Err(StateError::TooLong));
+    assert_eq!(plain.decode(&[b'0'; 4098]), Err(StateError::TooLong));
+}
+
+#[test]
+fn frame_state_should_be_bound_to_its_context() {
+    use frame_state::{FrameState, StateError};
+
+    let url = "https://pelican-fond-distinctly.ngrok-free.app/og";
+    let codec = FrameState::<u32>::with_key([1; 32]);
+    let bound = |fid: u64, url: &str| codec.bind(&[&fid.to_le_bytes(), url.as_bytes()]);
+    let encoded = bound(289309, url).encode(&7).unwrap();
+    assert_eq!(bound(289309, url).decode(encoded.as_bytes()), Ok(7));
+
+    // State moved to another fid, another frame or out of its context is rejected.
+    for other in [
+        bound(289310, url),
+        bound(289309, "https://frames.example.com"),
+        codec,
+    ] {
+        assert_eq!(
+            other.decode(encoded.as_bytes()),
+            Err(StateError::InvalidMac)
+        );
+    }
+    // Parts are length-prefixed, so they cannot be split differently.
+    assert_ne!(
+        codec.bind(&[b"ab", b"c"]).encode(&7),
+        codec.bind(&[b"a", b"bc"]).encode(&7)
+    );
+    // A codec without a key has no MAC to bind.
+    let plain = FrameState::<u32>::new();
+    assert_eq!(plain.bind(&[b"a"]).encode(&7), plain.encode(&7));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn frame_state_should_round_trip_json() {
+    use frame_state::{FrameState, StateError, STATE_MAC_LENGTH};
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Poll {
+        round: u32,
+        votes: Vec<u64>,
+    }
+
+    let poll = Poll {
+        round: 2,
+        votes: vec![289309, 3],
+    };
+    let plain = FrameState::<Poll>::new();
+    let encoded = plain.encode_json(&poll).unwrap();
+    assert_eq!(encoded, r#"{"round":2,"votes":[289309,3]}"#);
+    assert_eq!(plain.decode_json(encoded.as_bytes()), Ok(poll));
+
+    let signed = FrameState::<Poll>::with_key([1; 32]).bind(&[&289309u64.to_le_bytes()]);
+    let poll = plain.decode_json(encoded.as_bytes()).unwrap();
+    let signed_json = signed.encode_json(&poll).unwrap();
+    assert_eq!(signed_json.len(), encoded.len() + STATE_MAC_LENGTH * 2);
+    assert!(signed_json.starts_with(&encoded));
+    assert_eq!(signed.decode_json(signed_json.as_bytes()), Ok(poll));
+
+    let moved = FrameState::<Poll>::with_key([1; 32]).bind(&[&289310u64.to_le_bytes()]);
+    assert_eq!(
+        moved.decode_json(signed_json.as_bytes()),
+        Err(StateError::InvalidMac)
+    );
+    let tampered = signed_json.replacen("2", "3", 1);
+    assert_eq!(
+        signed.decode_json(tampered.as_bytes()),
+        Err(StateError::InvalidMac)
+    );
+    let unhex = encoded.clone() + &"z".repeat(STATE_MAC_LENGTH * 2);
+    assert_eq!(
+        signed.decode_json(unhex.as_bytes()),
+        Err(StateError::InvalidEncoding)
+    );
+    assert_eq!(
+        signed.decode_json(encoded.as_bytes()),
+        Err(StateError::InvalidMac)
+    );
+    assert_eq!(plain.decode_json(b"[1]"), Err(StateError::InvalidState));
+}
+
+#[test]
+fn transaction_frame_actions_should_be_checked() {
+    use transaction::{ChainId, FrameTransaction};
+
+    assert_eq!(
+        ChainId::parse("eip155:8453").unwrap().evm_chain_id(),
+        Some(8453)
+    );
+    assert_eq!(ChainId::evm(10).to_string(), "eip155:10");
+    let solana = ChainId::parse("solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ").unwrap();
+    assert_eq!(solana.namespace(), "solana");
+    assert_eq!(solana.evm_chain_id(), None);
+    for invalid in ["eip155", "ei:1", "EIP155:1", "eip155:", "eip155:1.0"] {
+        assert_eq!(
+            ChainId::parse(invalid),
+            Err(Error::InvalidChainId),
+            "{invalid}"
+        );
+    }
+
+    let action_with = |address: Vec<u8>, transaction_id: Vec<u8>| {
+        let mut data = generate_message().data.unwrap();
+        if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+            body.address = address;
+            body.transaction_id = transaction_id;
+        }
+        let raw = encode_proto_message(&sign_message(data)).unwrap();
+        parse_frame_action(&raw, &frame_action_config()).unwrap()
+    };
+
+    let action = action_with(vec![0xaa; 20], vec![0xbb; 32]);
+    let paid = FrameTransaction::try_from(&action).unwrap();
+    assert_eq!(
+        paid,
+        FrameTransaction {
+            fid: 289309,
+            address: [0xaa; 20],
+            transaction_id: [0xbb; 32],
+        }
+    );
+    assert_eq!(FrameTransaction::decode(&mut &paid.encode()[..]), Ok(paid));
+
+    // The first action of the flow only carries the connected address.
+    let action = action_with(vec![0xaa; 20], vec![]);
+    assert_eq!(action.address(), Ok(Some([0xaa; 20])));
+    assert_eq!(action.transaction_id(), Ok(None));
+    assert_eq!(
+        FrameTransaction::try_from(&action),
+        Err(Error::InvalidTransactionId)
+    );
+
+    let action = action_with(vec![0xaa; 32], vec![0xbb; 20]);
+    assert_eq!(action.address(), Err(Error::InvalidAddress));
+    assert_eq!(action.transaction_id(), Err(Error::InvalidTransactionId));
+    assert_eq!(
+        FrameTransaction::try_from(&action),
+        Err(Error::InvalidAddress)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn transaction_responses_should_round_trip() {
+    use transaction::{TransactionRequest, TransactionResponse};
+
+    let json = r#"{"chainId":"eip155:10","method":"eth_sendTransaction","params":{"abi":[],"to":"0x00000000fcce7f938e7ae6d3c335bd6a1a7c593d","value":"984316556204476","data":"0x783a112b"}}"#;
+    let response = TransactionResponse::from_json(json).unwrap();
+    assert_eq!(response.chain_id.evm_chain_id(), Some(10));
+    let TransactionRequest::SendTransaction(params) = &response.request else {
+        panic!("expected eth_sendTransaction");
+    };
+    assert_eq!(params.value.as_deref(), Some("984316556204476"));
+    assert_eq!(response.to_json(), json);
+
+    let json = r#"{"chainId":"eip155:8453","method":"eth_signTypedData_v4","params":{"domain":{"chainId":8453},"types":{"Vote":[{"name":"choice","type":"uint8"}]},"primaryType":"Vote","message":{"choice":1}},"attribution":false}"#;
+    let response = TransactionResponse::from_json(json).unwrap();
+    assert!(matches!(
+        &response.request,
+        TransactionRequest::SignTypedData(params) if params.primary_type == "Vote"
+    ));
+    assert_eq!(response.attribution, Some(false));
+    assert_eq!(response.to_json(), json);
+
+    let send = |chain_id: &str, to: &str, value: &str| {
+        TransactionResponse::from_json(&format!(
+            r#"{{"chainId":"{chain_id}","method":"eth_sendTransaction","params":{{"to":"{to}","value":"{value}"}}}}"#
+        ))
+    };
+    let to = "0x00000000fcce7f938e7ae6d3c335bd6a1a7c593d";
+    assert!(send("eip155:1", to, "0").is_ok());
+    assert_eq!(send("eip155", to, "0"), Err(Error::InvalidChainId));
+    assert_eq!(
+        send("solana:4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ", to, "0"),
+        Err(Error::InvalidChainId)
+    );
+    assert_eq!(send("eip155:1", &to[2..], "0"), Err(Error::InvalidAddress));
+    assert_eq!(send("eip155:1", "0x1234", "0"), Err(Error::InvalidAddress));
+    assert_eq!(send("eip155:1", to, "0x10"), Err(Error::InvalidMessage));
+    assert_eq!(
+        TransactionResponse::from_json(r#"{"chainId":"eip155:1","method":"eth_call"}"#),
+        Err(Error::InvalidMessage)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn message_json_should_match_hub_api() {
+    let msg = generate_message();
+    let json: serde_json::Value = serde_json::from_str(&msg.to_json()).unwrap();
+    assert_eq!(json["data"]["type"], "MESSAGE_TYPE_FRAME_ACTION");
+    assert_eq!(json["data"]["network"], "FARCASTER_NETWORK_MAINNET");
+    assert_eq!(json["data"]["frameActionBody"]["buttonIndex"], 1);
+    assert_eq!(
+        json["data"]["frameActionBody"]["url"],
+        "aHR0cHM6Ly9wZWxpY2FuLWZvbmQtZGlzdGluY3RseS5uZ3Jvay1mcmVlLmFwcC9vZw=="
+    );
+    assert_eq!(
+        json["data"]["frameActionBody"]["castId"]["hash"],
+        "0x0000000000000000000000000000000000000001"
+    );
+    assert_eq!(json["hash"], format!("0x{}", ::hex::encode(&msg.hash)));
+    assert_eq!(json["hashScheme"], "HASH_SCHEME_BLAKE3");
+    assert_eq!(json["signatureScheme"], "SIGNATURE_SCHEME_ED25519");
+    assert!(json.get("dataBytes").is_none());
+    let loaded = Message::from_json(&json.to_string()).unwrap();
+    assert_eq!(loaded, msg);
+    assert_eq!(validate_message(&loaded, &frame_action_config()), Ok(()));
+
+    let json = r#"{
+        "data": {
+            "type": "MESSAGE_TYPE_CAST_ADD",
+            "fid": 2,
+            "timestamp": 48994466,
+            "network": "FARCASTER_NETWORK_MAINNET",
+            "castAddBody": {
+                "embedsDeprecated": [],
+                "mentions": [3],
+                "parentCastId": { "fid": 226, "hash": "0xa48dd46161d8e57725f5e26e34ec19c13ff7f3b9" },
+                "text": "Cast Text",
+                "mentionsPositions": [5],
+                "embeds": [{ "url": "https://example.com" }]
+            }
+        },
+        "hash": "0xd2b1ddc6c88e865a33cb1a565e0058d757042974",
+        "hashScheme": "HASH_SCHEME_BLAKE3",
+        "signature": "3msLXzxB4eEYeF0Le6bWbZszDcCfsgwGMMK8bcRx0M8qkbE6ztLHo9TLRfVa9Wy0lbIkkI1GRUYFGUc7m1FmAg==",
+        "signatureScheme": "SIGNATURE_SCHEME_ED25519",
+        "signer": "0x78ff9a768cf1a6dc12d1a2a66a0c16cd1bd6b4d8f8cd2f09d3dac8213d55b1d4"
+    }"#;
+    let msg = Message::from_json(json).unwrap();
+    let data = msg.data.as_ref().unwrap();
+    assert_eq!(data.message_type(), Ok(MessageType::CastAdd));
+    let Some(message_data::Body::CastAddBody(body)) = &data.body else {
+        panic!("expected castAddBody");
+    };
+    assert_eq!(body.text, "Cast Text");
+    assert_eq!(body.mentions, vec![3]);
+    assert_eq!(
+        body.parent,
+        Some(cast_add_body::Parent::ParentCastId(CastId {
+            fid: 226,
+            hash: hex!("a48dd46161d8e57725f5e26e34ec19c13ff7f3b9").to_vec(),
+        }))
+    );
+    assert_eq!(
+        body.embeds[0].embed,
+        Some(embed::Embed::Url("https://example.com".into()))
+    );
+    assert_eq!(msg.signature.len(), 64);
+    assert_eq!(msg.signer.len(), 32);
+
+    // Enums also load from their numeric value; unknown names are rejected.
+    let numeric = json.replace("\"MESSAGE_TYPE_CAST_ADD\"", "1");
+    assert_eq!(Message::from_json(&numeric), Ok(msg));
+    let unknown = json.replace("MESSAGE_TYPE_CAST_ADD", "MESSAGE_TYPE_CAST_EDIT");
+    assert_eq!(Message::from_json(&unknown), Err(Error::InvalidMessage));
+}
+
+#[test]
+fn error_should_be_scale_encodable() {
+    let encoded = Error::WrongNetwork.encode();
+    assert_eq!(Error::decode(&mut &encoded[..]), Ok(Error::WrongNetwork));
+}
+
+#[cfg(feature = "pallet")]
+mod pallet {
+    use super::*;
+    use crate::mock::*;
+    use crate::pallet::{Error as PalletError, Event, WeightInfo};
+    use frame_support::dispatch::{DispatchResultWithPostInfo, GetDispatchInfo};
+    use frame_support::weights::Weight;
+    use frame_support::{assert_noop, assert_ok, BoundedVec};
+
+    /// Externalities whose clock reads `now` in Farcaster time.
+    fn new_test_ext_at(now: u32) -> sp_io::TestExternalities {
+        Now::set(FarcasterTime(now).to_unix_seconds());
+        new_test_ext()
+    }
+
+    fn submit(message: &Message) -> sp_runtime::DispatchResult {
+        submit_with_post_info(message)
+            .map(|_| ())
+            .map_err(|error| error.error)
+    }
+
+    fn submit_with_post_info(message: &Message) -> DispatchResultWithPostInfo {
+        let raw = BoundedVec::try_from(encode_proto_message(message).unwrap()).unwrap();
+        FarcasterFrame::submit_frame_action(RuntimeOrigin::signed(1), raw)
+    }
+
+    #[test]
+    fn submit_frame_action_should_work() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            let message = generate_message();
+            assert_ok!(submit(&message));
+
+            let url =
+                BoundedVec::try_from(b"https://pelican-fond-distinctly.ngrok-free.app/og".to_vec())
+                    .unwrap();
+            System::assert_last_event(
+                Event::FrameActionSubmitted {
+                    who: 1,
+                    fid: 289309,
+                    url,
+                    button_index: 1,
+                }
+                .into(),
+            );
+            assert_eq!(HANDLED.with(|handled| handled.take()), vec![(1, 289309, 1)]);
+        });
+    }
+
+    #[test]
+    fn submit_frame_action_dispatches_by_url_prefix() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            let with_url = |url: &[u8]| {
+                let mut data = generate_message().data.unwrap();
+                if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+                    body.url = url.to_vec();
+                }
+                sign_message(data)
+            };
+
+            // Unclaimed actions are only recorded in the event.
+            assert_ok!(submit(&with_url(b"https://other.example/frame")));
+            assert!(HANDLED.with(|handled| handled.take()).is_empty());
+
+            // A host that merely starts with a handler's host is not claimed by it.
+            assert_ok!(submit(&with_url(b"https://reject.example.evil/frame")));
+
+            assert_noop!(
+                submit(&with_url(b"https://reject.example/frame")),
+                sp_runtime::DispatchError::Other("rejected")
+            );
+        });
+    }
+
+    #[test]
+    fn submit_frame_action_weighs_the_handler() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            let with_url = |url: &[u8]| {
+                let mut data = generate_message().data.unwrap();
+                if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+                    body.url = url.to_vec();
+                }
+                data.timestamp += url.len() as u32;
+                sign_message(data)
+            };
+            let base = |message: &Message| {
+                <() as WeightInfo>::submit_frame_action(
+                    encode_proto_message(message).unwrap().len() as u32,
+                )
+            };
+
+            // The call is weighed for every handler, then charged for the one that ran.
+            let message = generate_message();
+            let raw = BoundedVec::try_from(encode_proto_message(&message).unwrap()).unwrap();
+            let call = crate::pallet::Call::<Test>::submit_frame_action { raw };
+            assert_eq!(
+                call.get_dispatch_info().call_weight,
+                base(&message) + Weight::from_parts(3_000_000, 0)
+            );
+            let post_info = submit_with_post_info(&message).unwrap();
+            assert_eq!(
+                post_info.actual_weight,
+                Some(base(&message) + Weight::from_parts(1_000_000, 0))
+            );
+
+            let unclaimed = with_url(b"https://other.example/frame");
+            let post_info = submit_with_post_info(&unclaimed).unwrap();
+            assert_eq!(post_info.actual_weight, Some(base(&unclaimed)));
+
+            let rejected = with_url(b"https://reject.example/frame");
+            let error = submit_with_post_info(&rejected).unwrap_err();
+            assert_eq!(
+                error.post_info.actual_weight,
+                Some(base(&rejected) + Weight::from_parts(500_000, 0))
+            );
+
+            // Messages that fail verification are charged without any handler.
+            let mut invalid = generate_message();
+            invalid.hash[0] ^= 0x01;
+            let error = submit_with_post_info(&invalid).unwrap_err();
+            assert_eq!(error.post_info.actual_weight, Some(base(&invalid)));
+        });
+    }
+
+    #[test]
+    fn submit_frame_action_rejects_replays() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            let at = |timestamp: u32| {
+                let mut data = generate_message().data.unwrap();
+                data.timestamp = timestamp;
+                sign_message(data)
+            };
+
+            assert_ok!(submit(&at(TIMESTAMP)));
+            assert_noop!(
+                submit(&at(TIMESTAMP)),
+                PalletError::<Test>::DuplicateMessage
+            );
+
+            assert_ok!(submit(&at(TIMESTAMP + 1)));
+            assert_ok!(submit(&at(TIMESTAMP + 2)));
+            assert_noop!(
+                submit(&at(TIMESTAMP + 3)),
+                PalletError::<Test>::TooManyMessages
+            );
+
+            // The window is measured from the current time, and a new message prunes
+            // everything outside it.
+            Now::set(FarcasterTime(TIMESTAMP + 601).to_unix_seconds());
+            assert_noop!(submit(&at(TIMESTAMP)), PalletError::<Test>::MessageTooOld);
+            assert_ok!(submit(&at(TIMESTAMP + 601)));
+            let seen = crate::pallet::SeenMessages::<Test>::get(289309);
+            let timestamps: Vec<_> = seen.iter().map(|m| m.timestamp).collect();
+            assert_eq!(
+                timestamps,
+                vec![TIMESTAMP + 1, TIMESTAMP + 2, TIMESTAMP + 601]
+            );
+        });
+    }
+
+    #[test]
+    fn prune_seen_messages_should_forget_stale_fids() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            System::set_block_number(1);
+            let at = |timestamp: u32| {
+                let mut data = generate_message().data.unwrap();
+                data.timestamp = timestamp;
+                sign_message(data)
+            };
+            let prune =
+                |fid: u64| FarcasterFrame::prune_seen_messages(RuntimeOrigin::signed(2), fid);
+
+            assert_ok!(submit(&at(TIMESTAMP)));
+            assert_ok!(submit(&at(TIMESTAMP + 300)));
+            assert_noop!(
+                prune(289309).map_err(|error| error.error),
+                PalletError::<Test>::NothingToPrune
+            );
+
+            // Only the messages outside the window are forgotten, for free.
+            Now::set(FarcasterTime(TIMESTAMP + 601).to_unix_seconds());
+            let post_info = prune(289309).unwrap();
+            assert_eq!(post_info.pays_fee, frame_support::dispatch::Pays::No);
+            System::assert_last_event(
+                Event::SeenMessagesPruned {
+                    fid: 289309,
+                    count: 1,
+                }
+                .into(),
+            );
+            let seen = crate::pallet::SeenMessages::<Test>::get(289309);
+            assert_eq!(seen.len(), 1);
+
+            // A fid with nothing left in the window is removed from storage.
+            Now::set(FarcasterTime(TIMESTAMP + 901).to_unix_seconds());
+            assert_ok!(prune(289309));
+            assert!(!crate::pallet::SeenMessages::<Test>::contains_key(289309));
+            assert_noop!(
+                prune(289309).map_err(|error| error.error),
+                PalletError::<Test>::NothingToPrune
+            );
+        });
+    }
+
+    #[test]
+    fn submit_frame_action_should_not_work() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            let mut message = generate_message();
+            message.hash[0] ^= 0x01;
+            assert_noop!(submit(&message), PalletError::<Test>::HashMismatch);
+
+            let mut data = generate_message().data.unwrap();
+            data.r#type = MessageType::CastAdd as i32;
+            assert_noop!(
+                submit(&sign_message(data)),
+                PalletError::<Test>::UnexpectedMessageType
+            );
+
+            let with_url_length = |len: usize| {
+                let mut data = generate_message().data.unwrap();
+                if let Some(message_data::Body::FrameActionBody(body)) = &mut data.body {
+                    body.url = format!("https://example.com/{}", "a".repeat(len - 20)).into_bytes();
+                }
+                sign_message(data)
+            };
+            assert_noop!(
+                submit(&with_url_length(129)),
+                PalletError::<Test>::UrlTooLong
+            );
+            assert_noop!(submit(&with_url_length(257)), PalletError::<Test>::TooLong);
+
+            let mut data = generate_message().data.unwrap();
+            data.network = FarcasterNetwork::Devnet as i32;
+            assert_noop!(
+                submit(&sign_message(data)),
+                PalletError::<Test>::WrongNetwork
+            );
+
+            let mut data = generate_message().data.unwrap();
+            data.timestamp = TIMESTAMP - 3601;
+            assert_noop!(
+                submit(&sign_message(data)),
+                PalletError::<Test>::TimestampTooOld
+            );
+
+            let raw = BoundedVec::try_from(vec![0x0a, 0x05]).unwrap();
+            assert_noop!(
+                FarcasterFrame::submit_frame_action(RuntimeOrigin::signed(1), raw)
+                    .map_err(|error| error.error),
+                PalletError::<Test>::InvalidProtobuf
+            );
+        });
+    }
+
+    #[test]
+    fn submit_frame_action_checks_frame_origins() {
+        new_test_ext_at(TIMESTAMP).execute_with(|| {
+            FrameOrigins::set(&["https://frames.example.com"]);
+            assert_noop!(
+                submit(&generate_message()),
+                PalletError::<Test>::UrlNotAllowed
+            );
+
+            FrameOrigins::set(&["https://pelican-fond-distinctly.ngrok-free.app"]);
+            assert_ok!(submit(&generate_message()));
+        });
+    }
+}
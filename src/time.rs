@@ -0,0 +1,98 @@
+//! Conversions between Farcaster time, Unix time and block-time moments.
+//!
+//! `MessageData.timestamp` counts seconds from the Farcaster epoch, 2021-01-01T00:00:00Z,
+//! not from the Unix epoch. Runtimes usually measure time as Unix milliseconds, the
+//! `Moment` of `pallet_timestamp`.
+use crate::message::MessageData;
+use crate::Error;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Unix time in seconds of the Farcaster epoch, 2021-01-01T00:00:00Z.
+pub const FARCASTER_EPOCH: u64 = 1_609_459_200;
+
+/// Seconds since the Farcaster epoch, the unit of `MessageData.timestamp`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Encode,
+    Decode,
+    MaxEncodedLen,
+    TypeInfo,
+)]
+pub struct FarcasterTime(pub u32);
+
+impl FarcasterTime {
+    /// Converts Unix time in seconds to Farcaster time.
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - Seconds since the Unix epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FarcasterTime)` if the time is representable.
+    /// * `Err(Error::InvalidTimestamp)` if it is before the Farcaster epoch or too far after it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::time::FarcasterTime;
+    ///
+    /// // 2024-03-01T09:13:52Z
+    /// let time = FarcasterTime::from_unix_seconds(1_709_284_432).unwrap();
+    /// assert_eq!(time, FarcasterTime(99_825_232));
+    /// ```
+    pub fn from_unix_seconds(seconds: u64) -> Result<Self, Error> {
+        let since_epoch = seconds
+            .checked_sub(FARCASTER_EPOCH)
+            .ok_or(Error::InvalidTimestamp)?;
+        u32::try_from(since_epoch)
+            .map(FarcasterTime)
+            .map_err(|_| Error::InvalidTimestamp)
+    }
+
+    /// Converts Unix time in milliseconds, such as a `pallet_timestamp` moment, to Farcaster
+    /// time. Sub-second precision is truncated.
+    ///
+    /// # Arguments
+    ///
+    /// * `millis` - Milliseconds since the Unix epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FarcasterTime)` if the time is representable.
+    /// * `Err(Error::InvalidTimestamp)` if it is before the Farcaster epoch or too far after it.
+    pub fn from_unix_millis(millis: u64) -> Result<Self, Error> {
+        Self::from_unix_seconds(millis / 1000)
+    }
+
+    /// Returns the time as seconds since the Unix epoch.
+    pub fn to_unix_seconds(self) -> u64 {
+        FARCASTER_EPOCH + u64::from(self.0)
+    }
+
+    /// Returns the time as milliseconds since the Unix epoch, the unit of `pallet_timestamp`.
+    pub fn to_unix_millis(self) -> u64 {
+        self.to_unix_seconds() * 1000
+    }
+}
+
+impl From<FarcasterTime> for u32 {
+    fn from(time: FarcasterTime) -> Self {
+        time.0
+    }
+}
+
+impl MessageData {
+    /// Returns `timestamp` as a [`FarcasterTime`].
+    pub fn time(&self) -> FarcasterTime {
+        FarcasterTime(self.timestamp)
+    }
+}
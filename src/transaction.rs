@@ -0,0 +1,315 @@
+//! Transaction frames: the chain ids, requests and results of the frame transaction flow.
+//!
+//! Pressing a `tx` button posts a frame action carrying the user's connected `address` to the
+//! button target, which answers with a [`TransactionResponse`] for the wallet to execute.
+//! Once the transaction is sent, the client posts another frame action carrying its
+//! `transaction_id`. A [`FrameTransaction`] holds what a verified action of that second kind
+//! attests: fid X paid from address Y in transaction Z.
+extern crate alloc;
+use crate::frame_action::VerifiedFrameAction;
+use crate::Error;
+use alloc::{borrow::ToOwned, string::String};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// Length in bytes of an EVM address.
+pub const ADDRESS_LENGTH: usize = 20;
+
+/// Length in bytes of an EVM transaction hash.
+pub const TRANSACTION_ID_LENGTH: usize = 32;
+
+/// A CAIP-2 chain id, such as `eip155:8453` for Base.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChainId {
+    namespace: String,
+    reference: String,
+}
+
+impl ChainId {
+    /// Parses a CAIP-2 chain id.
+    ///
+    /// # Arguments
+    ///
+    /// * `chain_id` - The chain id, as `namespace:reference`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ChainId)` if `chain_id` is a valid CAIP-2 chain id.
+    /// * `Err(Error::InvalidChainId)` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pallet_farcaster_frame::transaction::ChainId;
+    ///
+    /// let base = ChainId::parse("eip155:8453").unwrap();
+    /// assert_eq!(base.namespace(), "eip155");
+    /// assert_eq!(base.evm_chain_id(), Some(8453));
+    /// ```
+    pub fn parse(chain_id: &str) -> Result<Self, Error> {
+        let (namespace, reference) = chain_id.split_once(':').ok_or(Error::InvalidChainId)?;
+        let valid_namespace = (3..=8).contains(&namespace.len())
+            && namespace
+                .bytes()
+                .all(|b| b == b'-' || b.is_ascii_lowercase() || b.is_ascii_digit());
+        let valid_reference = (1..=32).contains(&reference.len())
+            && reference
+                .bytes()
+                .all(|b| b == b'-' || b == b'_' || b.is_ascii_alphanumeric());
+        if !valid_namespace || !valid_reference {
+            return Err(Error::InvalidChainId);
+        }
+        Ok(ChainId {
+            namespace: namespace.to_owned(),
+            reference: reference.to_owned(),
+        })
+    }
+
+    /// Returns the chain id of an EVM chain.
+    pub fn evm(chain_id: u64) -> Self {
+        ChainId {
+            namespace: "eip155".to_owned(),
+            reference: alloc::format!("{chain_id}"),
+        }
+    }
+
+    /// Returns the namespace, such as `eip155`.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Returns the reference within the namespace, such as `8453`.
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    /// Returns the EIP-155 chain id if this is an `eip155` chain.
+    pub fn evm_chain_id(&self) -> Option<u64> {
+        if self.namespace != "eip155" {
+            return None;
+        }
+        self.reference.parse().ok()
+    }
+}
+
+impl core::fmt::Display for ChainId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.namespace, self.reference)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ChainId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ChainId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let chain_id = String::deserialize(deserializer)?;
+        ChainId::parse(&chain_id).map_err(|_| serde::de::Error::custom("invalid CAIP-2 chain id"))
+    }
+}
+
+/// A transaction sent from a transaction frame, as attested by a verified frame action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct FrameTransaction {
+    /// Fid of the user who sent the transaction.
+    pub fid: u64,
+    /// The address the transaction was sent from.
+    pub address: [u8; ADDRESS_LENGTH],
+    /// Hash of the transaction.
+    pub transaction_id: [u8; TRANSACTION_ID_LENGTH],
+}
+
+impl TryFrom<&VerifiedFrameAction> for FrameTransaction {
+    type Error = Error;
+
+    fn try_from(action: &VerifiedFrameAction) -> Result<Self, Error> {
+        Ok(FrameTransaction {
+            fid: action.fid,
+            address: action.address()?.ok_or(Error::InvalidAddress)?,
+            transaction_id: action
+                .transaction_id()?
+                .ok_or(Error::InvalidTransactionId)?,
+        })
+    }
+}
+
+impl VerifiedFrameAction {
+    /// Returns the connected wallet address of a transaction frame action.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(address))` if the action carries a 20 byte address.
+    /// * `Ok(None)` if it carries none.
+    /// * `Err(Error::InvalidAddress)` if the address is not 20 bytes long.
+    pub fn address(&self) -> Result<Option<[u8; ADDRESS_LENGTH]>, Error> {
+        fixed(&self.address, Error::InvalidAddress)
+    }
+
+    /// Returns the transaction hash of a transaction frame action.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(hash))` if the action carries a 32 byte transaction id.
+    /// * `Ok(None)` if it carries none.
+    /// * `Err(Error::InvalidTransactionId)` if the transaction id is not 32 bytes long.
+    pub fn transaction_id(&self) -> Result<Option<[u8; TRANSACTION_ID_LENGTH]>, Error> {
+        fixed(&self.transaction_id, Error::InvalidTransactionId)
+    }
+}
+
+fn fixed<const N: usize>(bytes: &[u8], error: Error) -> Result<Option<[u8; N]>, Error> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+    bytes.try_into().map(Some).map_err(|_| error)
+}
+
+#[cfg(feature = "serde")]
+pub use response::*;
+
+#[cfg(feature = "serde")]
+mod response {
+    use super::{ChainId, ADDRESS_LENGTH};
+    use crate::Error;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    /// The JSON a transaction button target returns for the wallet to execute.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TransactionResponse {
+        /// Chain the transaction or signature is for.
+        pub chain_id: ChainId,
+        /// Wallet method to call, as the `method` and `params` fields.
+        #[serde(flatten)]
+        pub request: TransactionRequest,
+        /// Whether the client may append attribution calldata, `true` when not given.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub attribution: Option<bool>,
+    }
+
+    /// The wallet method of a [`TransactionResponse`] and its parameters.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "method", content = "params")]
+    pub enum TransactionRequest {
+        /// `eth_sendTransaction`: sends a transaction from the user's wallet.
+        #[serde(rename = "eth_sendTransaction")]
+        SendTransaction(SendTransactionParams),
+        /// `eth_signTypedData_v4`: signs EIP-712 typed data with the user's wallet.
+        #[serde(rename = "eth_signTypedData_v4")]
+        SignTypedData(SignTypedDataParams),
+    }
+
+    /// Parameters of `eth_sendTransaction`.
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    pub struct SendTransactionParams {
+        /// JSON ABI of the called functions and the errors they may return.
+        #[serde(default)]
+        pub abi: Vec<Value>,
+        /// Recipient as `0x`-prefixed hex.
+        pub to: String,
+        /// Wei to send as a decimal string.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub value: Option<String>,
+        /// Calldata as `0x`-prefixed hex.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub data: Option<String>,
+    }
+
+    /// Parameters of `eth_signTypedData_v4`.
+    #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SignTypedDataParams {
+        /// EIP-712 domain separator fields, such as `name`, `version` and `chainId`.
+        pub domain: Value,
+        /// Struct type definitions, keyed by type name.
+        pub types: Value,
+        /// Name of the type in `types` that `message` is an instance of.
+        pub primary_type: String,
+        /// The typed data to sign.
+        pub message: Value,
+    }
+
+    impl TransactionResponse {
+        /// Deserializes a transaction response from JSON.
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(TransactionResponse)` if the JSON is a valid response.
+        /// * `Err(Error::InvalidChainId)` if `chainId` is not a CAIP-2 `eip155` chain id.
+        /// * `Err(Error::InvalidAddress)` if `to` is not a `0x`-prefixed 20 byte address.
+        /// * `Err(Error::InvalidMessage)` if the JSON is malformed, or `value` or `data` is
+        ///   not a decimal or hex number.
+        ///
+        /// # Example
+        ///
+        /// ```
+        /// use pallet_farcaster_frame::transaction::{TransactionRequest, TransactionResponse};
+        ///
+        /// let json = r#"{
+        ///     "chainId": "eip155:10",
+        ///     "method": "eth_sendTransaction",
+        ///     "params": { "abi": [], "to": "0x00000000fcce7f938e7ae6d3c335bd6a1a7c593d", "value": "1" }
+        /// }"#;
+        /// let response = TransactionResponse::from_json(json).unwrap();
+        /// assert!(matches!(response.request, TransactionRequest::SendTransaction(_)));
+        /// ```
+        pub fn from_json(json: &str) -> Result<Self, Error> {
+            let response: Self = serde_json::from_str(json).map_err(|_| {
+                // A malformed `chainId` fails deserialization like any other field.
+                let chain_id = serde_json::from_str::<Value>(json)
+                    .ok()
+                    .and_then(|value| value.get("chainId")?.as_str().map(ChainId::parse));
+                match chain_id {
+                    Some(Err(error)) => error,
+                    _ => Error::InvalidMessage,
+                }
+            })?;
+            response.validate()?;
+            Ok(response)
+        }
+
+        /// Serializes the response to JSON.
+        pub fn to_json(&self) -> String {
+            serde_json::to_string(self).expect("responses always serialize")
+        }
+
+        /// Checks the response can be executed by an EVM wallet.
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(())` if the response is valid.
+        /// * `Err(Error)` describing the first problem, as for [`from_json`](Self::from_json).
+        pub fn validate(&self) -> Result<(), Error> {
+            if self.chain_id.evm_chain_id().is_none() {
+                return Err(Error::InvalidChainId);
+            }
+            let TransactionRequest::SendTransaction(params) = &self.request else {
+                return Ok(());
+            };
+            match decode_hex(&params.to) {
+                Some(to) if to.len() == ADDRESS_LENGTH => {}
+                _ => return Err(Error::InvalidAddress),
+            }
+            if let Some(value) = &params.value {
+                if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(Error::InvalidMessage);
+                }
+            }
+            if let Some(data) = &params.data {
+                decode_hex(data).ok_or(Error::InvalidMessage)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Decodes `0x`-prefixed hex.
+    fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+        hex::decode(hex.strip_prefix("0x")?).ok()
+    }
+}
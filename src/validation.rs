@@ -0,0 +1,252 @@
+//! Validation of a decoded `Message` against the caller's expectations.
+use crate::frame_action::validate_frame_action;
+use crate::limits::{validate_limits, MAX_DATA_BYTES_LENGTH};
+use crate::message::*;
+use crate::protobuf::ProtoMessage;
+use crate::time::FarcasterTime;
+use crate::verification::{verify_hash, verify_signature, HASH_LENGTH};
+use crate::Error;
+
+/// Seconds a message timestamp may run ahead of the current time, as allowed by hubs.
+pub const MAX_FUTURE_DRIFT: u32 = 10 * 60;
+
+/// Expectations a `Message` is checked against by [`validate_message`].
+#[derive(Clone, Copy, Debug)]
+pub struct ValidationConfig<'a> {
+    /// Network the message must be intended for, or `None` to accept any known network.
+    pub network: Option<FarcasterNetwork>,
+    /// Current time, or `None` to skip timestamp checks.
+    pub now: Option<FarcasterTime>,
+    /// Seconds `MessageData.timestamp` may be ahead of `now`.
+    pub max_future_drift: u32,
+    /// Seconds `MessageData.timestamp` may be behind `now`, or `None` to accept any age.
+    pub max_age: Option<u32>,
+    /// Message types to accept, or an empty slice to accept every type.
+    pub message_types: &'a [MessageType],
+    /// Origins frame action URLs must have, such as `https://frames.example.com`, or an empty
+    /// slice to accept any origin.
+    pub frame_origins: &'a [&'a str],
+}
+
+impl Default for ValidationConfig<'_> {
+    fn default() -> Self {
+        ValidationConfig {
+            network: None,
+            now: None,
+            max_future_drift: MAX_FUTURE_DRIFT,
+            max_age: None,
+            message_types: &[],
+            frame_origins: &[],
+        }
+    }
+}
+
+/// Runs every check on a `Message` and reports the first one that fails.
+///
+/// The checks run in this order: structure, size [`limits`](crate::limits), hash, signature,
+/// network, timestamp, body type, body consistency with the type and, for frame actions,
+/// [`validate_frame_action`]. When `data_bytes` is present, the checks after the hash run
+/// against the `MessageData` decoded from it, since that is what the hash and signature cover.
+///
+/// # Arguments
+///
+/// * `msg` - A reference to the `Message` to validate.
+/// * `config` - The expectations to check the message against.
+///
+/// # Returns
+///
+/// * `Ok(())` if every check passes.
+/// * `Err(Error)` describing the first check that failed.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{validate_message, Error, ValidationConfig, message::Message};
+///
+/// let msg = Message::default();
+/// assert_eq!(
+///     validate_message(&msg, &ValidationConfig::default()),
+///     Err(Error::MissingData)
+/// );
+/// ```
+pub fn validate_message(msg: &Message, config: &ValidationConfig) -> Result<(), Error> {
+    let decoded;
+    let data = match (&msg.data_bytes, &msg.data) {
+        (Some(data_bytes), _) => {
+            decoded = MessageData::decode_proto(data_bytes)?;
+            &decoded
+        }
+        (None, Some(data)) => data,
+        (None, None) => return Err(Error::MissingData),
+    };
+    validate_structure(msg, data)?;
+    validate_limits(data)?;
+    verify_hash(msg)?;
+    verify_signature(msg)?;
+    validate_network(data, config.network)?;
+    validate_timestamp(data, config)?;
+    validate_body_type(data, config)
+}
+
+fn validate_structure(msg: &Message, data: &MessageData) -> Result<(), Error> {
+    if data.fid == 0 {
+        return Err(Error::InvalidFid);
+    }
+    if msg.hash.len() != HASH_LENGTH {
+        return Err(Error::InvalidHash);
+    }
+    match &msg.data_bytes {
+        Some(data_bytes) if data_bytes.len() > MAX_DATA_BYTES_LENGTH as usize => {
+            Err(Error::TooLong)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `MessageData.network` names a Farcaster network, and the expected one if any.
+///
+/// # Arguments
+///
+/// * `data` - The `MessageData` to check.
+/// * `expected` - The network the message must be intended for, or `None` to accept any.
+///
+/// # Returns
+///
+/// * `Ok(())` if the network is acceptable.
+/// * `Err(Error::InvalidNetwork)` if `network` is `FarcasterNetwork::None` or unknown.
+/// * `Err(Error::WrongNetwork)` if `network` is not `expected`.
+pub fn validate_network(
+    data: &MessageData,
+    expected: Option<FarcasterNetwork>,
+) -> Result<(), Error> {
+    check_network(data.network, expected).map(|_| ())
+}
+
+/// [`validate_network`] over a raw `MessageData.network`, returning the network.
+pub(crate) fn check_network(
+    network: i32,
+    expected: Option<FarcasterNetwork>,
+) -> Result<FarcasterNetwork, Error> {
+    let network = match FarcasterNetwork::try_from(network) {
+        Ok(FarcasterNetwork::None) | Err(_) => return Err(Error::InvalidNetwork),
+        Ok(network) => network,
+    };
+    match expected {
+        Some(expected) if network != expected => Err(Error::WrongNetwork),
+        _ => Ok(network),
+    }
+}
+
+fn validate_timestamp(data: &MessageData, config: &ValidationConfig) -> Result<(), Error> {
+    check_timestamp(data.timestamp, config)
+}
+
+/// Checks a `MessageData.timestamp` against `config.now`.
+pub(crate) fn check_timestamp(timestamp: u32, config: &ValidationConfig) -> Result<(), Error> {
+    let Some(FarcasterTime(now)) = config.now else {
+        return Ok(());
+    };
+    if timestamp > now.saturating_add(config.max_future_drift) {
+        return Err(Error::TimestampInFuture);
+    }
+    match config.max_age {
+        Some(max_age) if timestamp < now.saturating_sub(max_age) => Err(Error::TimestampTooOld),
+        _ => Ok(()),
+    }
+}
+
+fn validate_body_type(data: &MessageData, config: &ValidationConfig) -> Result<(), Error> {
+    if data.body.is_none() {
+        return Err(Error::MissingBody);
+    }
+    if !config.message_types.is_empty()
+        && !config
+            .message_types
+            .iter()
+            .any(|message_type| data.message_type() == Ok(*message_type))
+    {
+        return Err(Error::UnexpectedMessageType);
+    }
+    validate_body(data)?;
+    match &data.body {
+        Some(message_data::Body::FrameActionBody(body)) => {
+            validate_frame_action(body, config.frame_origins)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `MessageData.body` is the body variant `MessageData.type` calls for.
+///
+/// # Arguments
+///
+/// * `data` - The `MessageData` to check.
+///
+/// # Returns
+///
+/// * `Ok(())` if the body matches the message type.
+/// * `Err(Error::MissingBody)` if there is no body.
+/// * `Err(Error::InvalidMessage)` if `type` is not a known `MessageType`.
+/// * `Err(Error::BodyMismatch)` if the body is not the variant of the message type.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::message::{message_data::Body, CastAddBody, MessageData, MessageType};
+/// use pallet_farcaster_frame::{validation::validate_body, Error};
+///
+/// let data = MessageData {
+///     r#type: MessageType::FrameAction as i32,
+///     body: Some(Body::CastAddBody(CastAddBody::default())),
+///     ..Default::default()
+/// };
+/// assert_eq!(validate_body(&data), Err(Error::BodyMismatch));
+/// ```
+pub fn validate_body(data: &MessageData) -> Result<(), Error> {
+    use message_data::Body;
+
+    let Some(body) = &data.body else {
+        return Err(Error::MissingBody);
+    };
+    let matches = match data.message_type()? {
+        MessageType::None => false,
+        MessageType::CastAdd => matches!(body, Body::CastAddBody(_)),
+        MessageType::CastRemove => matches!(body, Body::CastRemoveBody(_)),
+        MessageType::ReactionAdd | MessageType::ReactionRemove => {
+            matches!(body, Body::ReactionBody(_))
+        }
+        MessageType::LinkAdd | MessageType::LinkRemove => matches!(body, Body::LinkBody(_)),
+        MessageType::VerificationAddEthAddress => {
+            matches!(body, Body::VerificationAddAddressBody(_))
+        }
+        MessageType::VerificationRemove => matches!(body, Body::VerificationRemoveBody(_)),
+        MessageType::UserDataAdd => matches!(body, Body::UserDataBody(_)),
+        MessageType::UsernameProof => matches!(body, Body::UsernameProofBody(_)),
+        MessageType::FrameAction => matches!(body, Body::FrameActionBody(_)),
+        MessageType::LinkCompactState => matches!(body, Body::LinkCompactStateBody(_)),
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(Error::BodyMismatch)
+    }
+}
+
+/// Takes the `MessageData` covered by the message hash out of a `Message`.
+///
+/// # Arguments
+///
+/// * `msg` - The `Message` to consume.
+///
+/// # Returns
+///
+/// * `Ok(MessageData)` decoded from `data_bytes` when present, or taken from `data`.
+/// * `Err(Error::InvalidProtobuf)` or `Err(Error::TooLong)` if `data_bytes` cannot be decoded.
+/// * `Err(Error::MissingData)` if the message carries neither.
+pub fn into_message_data(msg: Message) -> Result<MessageData, Error> {
+    match (msg.data_bytes, msg.data) {
+        (Some(data_bytes), _) => Ok(MessageData::decode_proto(&data_bytes)?),
+        (None, Some(data)) => Ok(data),
+        (None, None) => Err(Error::MissingData),
+    }
+}
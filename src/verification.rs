@@ -0,0 +1,144 @@
+//! Verification of the hash and signature carried by a Farcaster `Message`.
+use crate::eip712;
+use crate::message::*;
+use crate::protobuf::ProtoMessage;
+use crate::Error;
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// Length in bytes of a Farcaster message hash: a blake3 digest truncated to 160 bits.
+pub const HASH_LENGTH: usize = 20;
+
+/// Computes the Farcaster hash of serialized `MessageData` bytes.
+///
+/// # Arguments
+///
+/// * `data` - The protobuf-encoded `MessageData`.
+///
+/// # Returns
+///
+/// * The first 20 bytes of the blake3 digest of `data`.
+pub fn compute_hash(data: &[u8]) -> [u8; HASH_LENGTH] {
+    let digest = blake3::hash(data);
+    let mut hash = [0u8; HASH_LENGTH];
+    hash.copy_from_slice(&digest.as_bytes()[..HASH_LENGTH]);
+    hash
+}
+
+/// Checks that `Message.hash` is the digest of the message's data.
+///
+/// The digest covers `data_bytes` when present, and the canonical protobuf encoding of
+/// `data` otherwise.
+///
+/// # Arguments
+///
+/// * `msg` - A reference to the `Message` to verify.
+///
+/// # Returns
+///
+/// * `Ok(())` if the hash matches.
+/// * `Err(Error::UnsupportedHashScheme)` if `hash_scheme` is not `HashScheme::Blake3`.
+/// * `Err(Error::MissingData)` if the message carries neither `data` nor `data_bytes`.
+/// * `Err(Error::HashMismatch)` if the hash does not match the data.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{verify_hash, Error, message::Message};
+///
+/// let msg = Message {
+///     hash_scheme: 1,           // Blake3
+///     data_bytes: Some(vec![]), // Empty data
+///     hash: vec![0; 20],        // Not the digest of the data
+///     ..Default::default()
+/// };
+/// assert_eq!(verify_hash(&msg), Err(Error::HashMismatch));
+/// ```
+pub fn verify_hash(msg: &Message) -> Result<(), Error> {
+    if msg.hash_scheme() != Ok(HashScheme::Blake3) {
+        return Err(Error::UnsupportedHashScheme);
+    }
+    let hash = match (&msg.data_bytes, &msg.data) {
+        (Some(data_bytes), _) => compute_hash(data_bytes),
+        (None, Some(data)) => compute_hash(&data.encode_proto()),
+        (None, None) => return Err(Error::MissingData),
+    };
+    if msg.hash[..] == hash[..] {
+        Ok(())
+    } else {
+        Err(Error::HashMismatch)
+    }
+}
+
+/// Checks that `Message.signature` was produced by `Message.signer` over `Message.hash`.
+///
+/// Ed25519 signatures are checked against the `signer` public key. EIP-712 signatures are
+/// checked by recovering the Ethereum address that signed and comparing it to `signer`.
+///
+/// # Arguments
+///
+/// * `msg` - A reference to the `Message` to verify.
+///
+/// # Returns
+///
+/// * `Ok(())` if the signature is valid.
+/// * `Err(Error::UnsupportedSignatureScheme)` if `signature_scheme` is neither
+///   `SignatureScheme::Ed25519` nor `SignatureScheme::Eip712`.
+/// * `Err(Error::InvalidSigner)` if `signer` is not an ed25519 public key, or not an
+///   Ethereum address for EIP-712 signatures.
+/// * `Err(Error::InvalidSignature)` if the signature does not verify.
+///
+/// # Example
+///
+/// ```
+/// use pallet_farcaster_frame::{verify_signature, Error, message::Message};
+///
+/// let msg = Message {
+///     signature_scheme: 1,      // Ed25519
+///     signer: vec![0; 31],      // Too short for a public key
+///     ..Default::default()
+/// };
+/// assert_eq!(verify_signature(&msg), Err(Error::InvalidSigner));
+/// ```
+pub fn verify_signature(msg: &Message) -> Result<(), Error> {
+    verify_signature_parts(msg.signature_scheme, &msg.hash, &msg.signature, &msg.signer)
+}
+
+/// [`verify_signature`] over the fields of a `Message`, so borrowed views can be checked.
+pub(crate) fn verify_signature_parts(
+    signature_scheme: i32,
+    hash: &[u8],
+    signature: &[u8],
+    signer: &[u8],
+) -> Result<(), Error> {
+    match SignatureScheme::try_from(signature_scheme) {
+        Ok(SignatureScheme::Ed25519) => verify_ed25519(hash, signature, signer),
+        Ok(SignatureScheme::Eip712) => eip712::verify_message_signature(hash, signature, signer),
+        _ => Err(Error::UnsupportedSignatureScheme),
+    }
+}
+
+fn verify_ed25519(hash: &[u8], signature: &[u8], signer: &[u8]) -> Result<(), Error> {
+    let signer: &[u8; 32] = signer.try_into().map_err(|_| Error::InvalidSigner)?;
+    let key = VerifyingKey::from_bytes(signer).map_err(|_| Error::InvalidSigner)?;
+    let signature = Signature::from_slice(signature).map_err(|_| Error::InvalidSignature)?;
+    key.verify_strict(hash, &signature)
+        .map_err(|_| Error::InvalidSignature)
+}
+
+/// Checks both the hash and the signature of a `Message`.
+///
+/// The hash is checked first, so a valid signature over a hash that does not match the
+/// data is still rejected.
+///
+/// # Arguments
+///
+/// * `msg` - A reference to the `Message` to verify.
+///
+/// # Returns
+///
+/// * `Ok(())` if both checks pass.
+/// * `Err(Error)` from [`verify_hash`] or [`verify_signature`] otherwise.
+pub fn verify_message(msg: &Message) -> Result<(), Error> {
+    verify_hash(msg)?;
+    verify_signature(msg)
+}
@@ -0,0 +1,173 @@
+//! Runs the `farcaster-frame` binary against messages signed with `MessageBuilder`.
+use base64::prelude::*;
+use pallet_farcaster_frame::builder::{MessageBuilder, SigningKey};
+use pallet_farcaster_frame::message::{FarcasterNetwork, FrameActionBody, Message};
+use pallet_farcaster_frame::{encode_message, encode_proto_message, FarcasterTime};
+use std::process::{Command, Output};
+
+fn frame_action() -> Message {
+    frame_action_at(FarcasterTime(99_825_232))
+}
+
+fn frame_action_at(timestamp: FarcasterTime) -> Message {
+    let body = FrameActionBody {
+        url: b"https://frames.example.com/vote".to_vec(),
+        button_index: 2,
+        ..Default::default()
+    };
+    MessageBuilder::new(2, FarcasterNetwork::Mainnet, timestamp)
+        .frame_action(body)
+        .sign(&SigningKey::from_bytes(&[7; 32]))
+        .unwrap()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_farcaster-frame"))
+        .args(args)
+        .output()
+        .expect("Expected farcaster-frame to run")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn decode_should_read_hex_and_base64() {
+    let raw = encode_proto_message(&frame_action()).unwrap();
+
+    let from_hex = run(&["decode", &format!("0x{}", hex::encode(&raw))]);
+    assert_eq!(from_hex.status.code(), Some(0), "{}", stderr(&from_hex));
+    let json = stdout(&from_hex);
+    assert!(
+        json.contains(r#""type": "MESSAGE_TYPE_FRAME_ACTION""#),
+        "{json}"
+    );
+    assert!(json.contains(r#""buttonIndex": 2"#), "{json}");
+    assert_eq!(
+        Message::from_json(&json),
+        Ok(frame_action()),
+        "decode prints hub JSON"
+    );
+
+    let from_base64 = run(&["decode", &BASE64_STANDARD.encode(&raw)]);
+    assert_eq!(
+        from_base64.status.code(),
+        Some(0),
+        "{}",
+        stderr(&from_base64)
+    );
+    assert_eq!(stdout(&from_base64), json);
+}
+
+#[test]
+fn verify_should_report_invalid_messages() {
+    let message = frame_action();
+    let valid = run(&[
+        "verify",
+        &hex::encode(encode_proto_message(&message).unwrap()),
+    ]);
+    assert_eq!(valid.status.code(), Some(0), "{}", stderr(&valid));
+    assert_eq!(stdout(&valid).trim(), "valid");
+
+    let mut tampered = message.clone();
+    tampered.signature[0] ^= 1;
+    let invalid = run(&[
+        "verify",
+        &hex::encode(encode_proto_message(&tampered).unwrap()),
+    ]);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert_eq!(stderr(&invalid).trim(), "error: InvalidSignature");
+
+    let raw = hex::encode(encode_proto_message(&message).unwrap());
+    let wrong_network = run(&["verify", "--network", "testnet", &raw]);
+    assert_eq!(wrong_network.status.code(), Some(1));
+    assert_eq!(stderr(&wrong_network).trim(), "error: WrongNetwork");
+    let wrong_type = run(&["verify", "--type", "cast_add", &raw]);
+    assert_eq!(wrong_type.status.code(), Some(1));
+    assert_eq!(stderr(&wrong_type).trim(), "error: UnexpectedMessageType");
+}
+
+#[test]
+fn verify_should_reject_messages_from_the_future() {
+    // About 2147, whatever the clock of the machine running the test.
+    let future = frame_action_at(FarcasterTime(4_000_000_000));
+    let output = run(&[
+        "verify",
+        &hex::encode(encode_proto_message(&future).unwrap()),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output).trim(), "error: TimestampInFuture");
+}
+
+#[test]
+fn input_encoding_should_be_detected() {
+    let raw = encode_proto_message(&frame_action()).unwrap();
+    let json = stdout(&run(&["decode", &hex::encode(&raw)]));
+
+    // Hex digits that do not decode as hex are tried as base64, and the error names the flag.
+    let odd = format!("{}0", hex::encode(&raw));
+    let output = run(&["decode", &odd]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("--encoding"),
+        "{}",
+        stderr(&output)
+    );
+    // Text with other characters is base64.
+    let base64 = BASE64_STANDARD.encode(&raw);
+    assert!(!base64.bytes().all(|b| b.is_ascii_hexdigit()));
+    assert_eq!(stdout(&run(&["decode", &base64])), json);
+    // `0x` always means hex.
+    let output = run(&["decode", &format!("0x{base64}")]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        stderr(&output).contains("--encoding"),
+        "{}",
+        stderr(&output)
+    );
+
+    // Input that decodes as hex is hex, unless `--encoding` says otherwise.
+    let as_base64 = run(&["decode", "--encoding", "base64", &hex::encode(&raw)]);
+    assert_eq!(as_base64.status.code(), Some(2));
+    assert!(stderr(&as_base64).contains("not valid base64"));
+}
+
+#[test]
+fn convert_should_round_trip_through_scale() {
+    let message = frame_action();
+    let protobuf = hex::encode(encode_proto_message(&message).unwrap());
+
+    let scale = run(&["convert", "--to", "scale", &protobuf]);
+    assert_eq!(scale.status.code(), Some(0), "{}", stderr(&scale));
+    let scale = stdout(&scale).trim().to_owned();
+    assert_eq!(scale, hex::encode(encode_message(&message).unwrap()));
+
+    let back = run(&["convert", "--from", "scale", "--to", "protobuf", &scale]);
+    assert_eq!(back.status.code(), Some(0), "{}", stderr(&back));
+    assert_eq!(stdout(&back).trim(), protobuf);
+}
+
+#[test]
+fn bad_usage_should_exit_with_2() {
+    let raw = hex::encode(encode_proto_message(&frame_action()).unwrap());
+    for args in [
+        &["frobnicate", raw.as_str()][..],
+        &["decode"],
+        &["decode", "--bogus", &raw],
+        &["decode", "--encoding", "hex", "not hex"],
+        &["convert", &raw],
+        &["verify", "--network", "nowhere", &raw],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(
+            stderr(&output).contains("Usage: farcaster-frame"),
+            "{args:?}"
+        );
+    }
+}